@@ -0,0 +1,31 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// With the `embed-inputs` feature, generate a table of every `problems/*.txt` file,
+// each baked in with `include_str!`, for `src/embedded.rs` to include.
+fn main() {
+    let out_file = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_inputs.rs");
+    let mut table = String::from("pub static EMBEDDED_INPUTS: &[(&str, &str)] = &[\n");
+
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        let problems_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("problems");
+        println!("cargo:rerun-if-changed={}", problems_dir.display());
+
+        let mut input_files = fs::read_dir(&problems_dir)
+            .expect("read problems directory")
+            .map(|entry| entry.expect("read problems directory entry").path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+            .collect::<Vec<_>>();
+        input_files.sort();
+        for path in input_files {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+            table.push_str(&format!("    ({:?}, include_str!({:?})),\n", file_name, path.display().to_string()));
+        }
+    }
+
+    table.push_str("];\n");
+    fs::write(out_file, table).expect("write embedded inputs table");
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::file::read_input;
+
+pub const DEFAULT_ANSWERS_FILE: &str = "problems/answers.txt";
+// The answers to each day's example, as given in the puzzle text.
+pub const DEFAULT_EXAMPLE_ANSWERS_FILE: &str = "problems/example-answers.txt";
+
+// The known correct answers, keyed by selector (e.g. "5a"). On disk this is a plain
+// text file with one "<selector> <answer>" pair per line; blank lines and lines
+// starting with '#' are ignored, but kept when the answers are saved.
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: BTreeMap<String, String>,
+    // every line of the file, so that saving only changes the answers
+    lines: Vec<String>,
+}
+
+impl Answers {
+    // Load the answers file at `path`. A missing file is treated as having no answers.
+    pub fn load(path: &str) -> Result<Answers, Error> {
+        let contents = match read_input(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Answers::default()),
+            Err(err) => return Err(Error::from(err).in_file(path)),
+        };
+        Answers::parse(&contents).map_err(|err| err.in_file(path))
+    }
+
+    pub fn parse(contents: &str) -> Result<Answers, Error> {
+        let mut answers = BTreeMap::new();
+        for (line_index, line) in contents.lines().enumerate() {
+            let trimmed_line = line.trim();
+            if trimmed_line.is_empty() || trimmed_line.starts_with('#') {
+                continue;
+            }
+            match trimmed_line.split_once(char::is_whitespace) {
+                Some((selector, answer)) if !answer.trim().is_empty() => {
+                    answers.insert(selector.to_string(), answer.trim().to_string());
+                }
+                _ => return Err(Error::parse(line_index, line, line.len(), "a selector followed by its answer, e.g. '5a 12345'")),
+            }
+        }
+
+        Ok(Answers { answers, lines: contents.lines().map(String::from).collect() })
+    }
+
+    pub fn get(&self, selector: &str) -> Option<&str> {
+        self.answers.get(selector).map(|answer| answer.as_str())
+    }
+
+    // Every selector with a recorded answer, in selector order.
+    pub fn selectors(&self) -> impl Iterator<Item = &str> {
+        self.answers.keys().map(|selector| selector.as_str())
+    }
+
+    // Record the answer for `selector`, replacing the line with its earlier answer (the
+    // last one, if there are several) or adding a line at the end.
+    pub fn insert(&mut self, selector: &str, answer: &str) {
+        self.answers.insert(selector.to_string(), answer.to_string());
+        let line = format!("{} {}", selector, answer);
+        let existing = self.lines.iter().rposition(|line| {
+            let trimmed_line = line.trim();
+            !trimmed_line.starts_with('#') && trimmed_line.split_whitespace().next() == Some(selector)
+        });
+        match existing {
+            Some(index) => self.lines[index] = line,
+            None => self.lines.push(line),
+        }
+    }
+
+    // Write the answers back to `path`: the lines they were loaded from (comments
+    // included), with any new answers added at the end.
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let contents = self.lines.iter().map(|line| format!("{}\n", line)).collect::<String>();
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).map_err(|err| Error::from(err).in_file(path))?;
+            }
+        }
+        fs::write(path, contents).map_err(|err| Error::from(err).in_file(path))
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::runner::format_duration;
+use crate::solution::Solution;
+
+// How long to spend warming up before measuring, and roughly how long to spend
+// measuring. The number of measured iterations is derived from how long a warm-up
+// iteration took, so fast solvers get many samples and slow solvers only a few.
+pub struct BenchConfig {
+    pub warmup_time: Duration,
+    pub measurement_time: Duration,
+    pub min_iterations: usize,
+    pub max_iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup_time: Duration::from_millis(500),
+            measurement_time: Duration::from_secs(3),
+            min_iterations: 5,
+            max_iterations: 100_000,
+        }
+    }
+}
+
+// Summary statistics over the measured iterations of a benchmark.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub warmup_iterations: usize,
+    pub iterations: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    // Compute statistics over a non-empty set of samples.
+    pub fn from_samples(samples: &mut [Duration], warmup_iterations: usize) -> Stats {
+        samples.sort();
+        let count = samples.len();
+        let secs = samples.iter().map(|sample| sample.as_secs_f64()).collect::<Vec<f64>>();
+        let mean = secs.iter().sum::<f64>() / count as f64;
+        let variance = secs.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / count as f64;
+
+        let median = if count.is_multiple_of(2) {
+            (samples[count / 2 - 1] + samples[count / 2]) / 2
+        } else {
+            samples[count / 2]
+        };
+        // nearest-rank percentile, so the p95 is always one of the samples
+        let p95_rank = ((count as f64) * 0.95).ceil() as usize;
+
+        return Stats {
+            warmup_iterations,
+            iterations: count,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            p95: samples[p95_rank.clamp(1, count) - 1],
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        };
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} iterations after {} warm-up runs", self.iterations, self.warmup_iterations)?;
+        writeln!(f, "  min     {:>10}", format_duration(self.min))?;
+        writeln!(f, "  median  {:>10}", format_duration(self.median))?;
+        writeln!(f, "  mean    {:>10}", format_duration(self.mean))?;
+        writeln!(f, "  p95     {:>10}", format_duration(self.p95))?;
+        write!(f, "  stddev  {:>10}", format_duration(self.std_dev))
+    }
+}
+
+// Repeatedly parse and solve `input`, returning statistics on how long each run took.
+// Reading the input is not included. Fails if any run fails.
+pub fn bench_solution(solution: &dyn Solution, input: &str, config: &BenchConfig) -> Result<Stats, Error> {
+    let warmup_start = Instant::now();
+    let mut warmup_iterations = 0;
+    while warmup_iterations == 0 || warmup_start.elapsed() < config.warmup_time {
+        black_box(solution.solve_input(black_box(input))?);
+        warmup_iterations += 1;
+    }
+    let estimate = warmup_start.elapsed().as_secs_f64() / warmup_iterations as f64;
+
+    let iterations = if estimate > 0.0 {
+        (config.measurement_time.as_secs_f64() / estimate) as usize
+    } else {
+        config.max_iterations
+    };
+    let iterations = iterations.clamp(config.min_iterations.max(1), config.max_iterations.max(1));
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        black_box(solution.solve_input(black_box(input))?);
+        samples.push(start.elapsed());
+    }
+
+    return Ok(Stats::from_samples(&mut samples, warmup_iterations));
+}
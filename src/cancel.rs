@@ -0,0 +1,55 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::Error;
+
+// A flag shared between a solver and whoever is waiting on it, which asks the solver
+// to give up. Cancellation is cooperative: it only takes effect at the points where
+// the solver calls `check`.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+// Run `f` (a solver) on this thread with `token` as its cancellation token.
+pub fn with_token<T>(token: CancellationToken, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_TOKEN.with(|current| current.replace(Some(token)));
+    let value = f();
+    CURRENT_TOKEN.with(|current| current.replace(previous));
+    return value;
+}
+
+// The cancellation token of the solver running on this thread, if it has one.
+pub fn current() -> Option<CancellationToken> {
+    CURRENT_TOKEN.with(|current| current.borrow().clone())
+}
+
+// Whether the solver running on this thread has been asked to give up. Solvers with
+// long loops call this every so often and return its error, e.g. `cancel::check()?`.
+// Outside of `with_token` this never fails.
+pub fn check() -> Result<(), Error> {
+    let cancelled = CURRENT_TOKEN.with(|current| current.borrow().as_ref().is_some_and(CancellationToken::is_cancelled));
+    if cancelled {
+        return Err(Error::Cancelled);
+    }
+    return Ok(());
+}
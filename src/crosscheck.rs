@@ -0,0 +1,291 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use crate::error::Error;
+use crate::exit::Exit;
+use crate::file::{read_input, InputOptions};
+use crate::problems::{implementations, SOLUTIONS};
+use crate::rng::{seed_or_clock, Rng};
+use crate::solution::{Part, Solution};
+
+// How many generated inputs each part is cross-checked on, as well as its real and
+// example inputs.
+pub const GENERATED_INPUTS: u64 = 20;
+
+pub struct CrosscheckInput {
+    // where the input came from, e.g. a path or "generated (seed 42)"
+    pub name: String,
+    pub contents: String,
+}
+
+// What one implementation made of an input.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Answer(String),
+    // The implementation returned an error, e.g. because it couldn't parse the input
+    Failed(String),
+    Panicked(String),
+}
+
+impl Outcome {
+    // Implementations agree if they give the same answer, or both reject the input, or
+    // both panic on it. Messages aren't compared, since implementations may parse (and
+    // index) differently.
+    fn agrees_with(&self, other: &Outcome) -> bool {
+        match (self, other) {
+            (Outcome::Answer(answer), Outcome::Answer(other_answer)) => answer == other_answer,
+            (Outcome::Failed(_), Outcome::Failed(_)) => true,
+            (Outcome::Panicked(_), Outcome::Panicked(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Answer(answer) => write!(f, "{}", answer),
+            Outcome::Failed(err) => write!(f, "error: {}", err.replace('\n', " ")),
+            Outcome::Panicked(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+pub struct Disagreement {
+    pub input_name: String,
+    // each implementation's outcome on the input, in the same order as the implementations
+    pub outcomes: Vec<Outcome>,
+    // the smallest input found (by removing lines, then characters) that still disagrees
+    pub minimized_input: String,
+    pub minimized_outcomes: Vec<Outcome>,
+}
+
+// The result of cross-checking every implementation of one part.
+pub struct Crosscheck {
+    // the main implementation first, then its variants
+    pub implementations: Vec<&'static dyn Solution>,
+    pub inputs_checked: usize,
+    // the first input the implementations disagreed on, if any
+    pub disagreement: Option<Disagreement>,
+}
+
+// Cross-check every part of `day` that has variants and print the results, returning
+// a mismatch if any implementations disagreed. Inputs are generated from `seed`, or
+// the clock (see `seed_or_clock`) once the day is known to have something to check.
+pub fn run_crosschecks(day: u32, input_options: &InputOptions, seed: Option<u64>, size: usize) -> Result<Exit, Error> {
+    if !SOLUTIONS.iter().any(|solution| solution.day() == day) {
+        return Err(Error::Validation(format!("there are no problems for day {}", day)));
+    }
+    if !has_variants(day) {
+        return Err(Error::Validation(format!("day {} has no variants to cross-check against", day)));
+    }
+
+    let crosschecks = crosscheck_day(day, input_options, seed_or_clock(seed), size);
+    print_crosschecks(&crosschecks);
+    if crosschecks.iter().any(|crosscheck| crosscheck.disagreement.is_some()) {
+        return Ok(Exit::Mismatch);
+    }
+    return Ok(Exit::Success);
+}
+
+// Cross-check each part of `day` that has variants, on its real and example inputs
+// (when present) and on inputs generated from consecutive seeds starting at `seed`.
+// Parts without variants are left out, so the result is empty if the day has none.
+pub fn crosscheck_day(day: u32, input_options: &InputOptions, seed: u64, size: usize) -> Vec<Crosscheck> {
+    let mut crosschecks = Vec::new();
+    for part in [Part::A, Part::B] {
+        let implementations = implementations(day, part);
+        if implementations.len() < 2 {
+            continue;
+        }
+        let inputs = crosscheck_inputs(implementations[0], input_options, seed, size);
+        crosschecks.push(crosscheck(implementations, &inputs));
+    }
+
+    return crosschecks;
+}
+
+// Whether any part of `day` has variants to cross-check against.
+pub fn has_variants(day: u32) -> bool {
+    [Part::A, Part::B].into_iter().any(|part| implementations(day, part).len() > 1)
+}
+
+// The real and example inputs for a solution that can be read and aren't empty,
+// followed by `GENERATED_INPUTS` generated ones if the day has a generator.
+pub fn crosscheck_inputs(solution: &dyn Solution, input_options: &InputOptions, seed: u64, size: usize) -> Vec<CrosscheckInput> {
+    let mut inputs = Vec::new();
+    for example in [false, true] {
+        let options = InputOptions { example, dir: input_options.dir.clone(), ..*input_options };
+        let path = options.resolve(solution.day(), solution.part());
+        match read_input(&path) {
+            Ok(contents) if !contents.trim().is_empty() => inputs.push(CrosscheckInput { name: path, contents }),
+            _ => {}
+        }
+    }
+    for index in 0..GENERATED_INPUTS {
+        // seeds wrap around, so that a seed near the end still gets every input
+        let seed = seed.wrapping_add(index);
+        if let Some(contents) = solution.generate(&mut Rng::new(seed), size) {
+            inputs.push(CrosscheckInput { name: format!("generated (seed {})", seed), contents });
+        }
+    }
+    return inputs;
+}
+
+// Run every implementation on each input in turn, stopping at the first input they
+// disagree on.
+pub fn crosscheck(implementations: Vec<&'static dyn Solution>, inputs: &[CrosscheckInput]) -> Crosscheck {
+    for (index, input) in inputs.iter().enumerate() {
+        let outcomes = run_implementations(&implementations, &input.contents);
+        if all_agree(&outcomes) {
+            continue;
+        }
+
+        let minimized_input = minimize_input(&input.contents, |candidate| !all_agree(&run_implementations(&implementations, candidate)));
+        let minimized_outcomes = run_implementations(&implementations, &minimized_input);
+        let disagreement = Disagreement { input_name: input.name.clone(), outcomes, minimized_input, minimized_outcomes };
+        return Crosscheck { implementations, inputs_checked: index + 1, disagreement: Some(disagreement) };
+    }
+
+    return Crosscheck { implementations, inputs_checked: inputs.len(), disagreement: None };
+}
+
+fn run_implementations(implementations: &[&'static dyn Solution], input: &str) -> Vec<Outcome> {
+    let mut outcomes = Vec::new();
+    for solution in implementations {
+        let outcome = match catch_panic_silently(|| solution.solve_input(input)) {
+            Ok(Ok(solved)) => Outcome::Answer(solved.answer.to_string()),
+            Ok(Err(err)) => Outcome::Failed(err.to_string()),
+            Err(payload) => {
+                let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+                    (Some(message), _) => message.to_string(),
+                    (_, Some(message)) => message.clone(),
+                    _ => String::from("unknown panic"),
+                };
+                Outcome::Panicked(message)
+            }
+        };
+        outcomes.push(outcome);
+    }
+    return outcomes;
+}
+
+thread_local! {
+    static SILENCE_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+// Run `f`, catching any panic without printing it: implementations are expected to
+// panic on some inputs, which is reported rather than printed as it happens. The
+// panic hook is only wrapped (once), so panics on other threads are printed as usual.
+fn catch_panic_silently<T>(f: impl FnOnce() -> T) -> Result<T, Box<dyn Any + Send>> {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCE_PANICS.try_with(Cell::get).unwrap_or(false) {
+                previous_hook(info);
+            }
+        }));
+    });
+
+    let was_silenced = SILENCE_PANICS.with(|silenced| silenced.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    SILENCE_PANICS.with(|silenced| silenced.set(was_silenced));
+    return result;
+}
+
+fn all_agree(outcomes: &[Outcome]) -> bool {
+    outcomes.windows(2).all(|pair| pair[0].agrees_with(&pair[1]))
+}
+
+// Shrink an input while `still_fails` holds for it: first by removing runs of lines,
+// then runs of columns (the same character positions from every line, which keeps a
+// grid aligned), then runs of characters within each remaining line. Each pass tries
+// removing halves, then quarters and so on down to single lines or characters.
+pub fn minimize_input(input: &str, still_fails: impl Fn(&str) -> bool) -> String {
+    let join_lines = |lines: &[String]| lines.iter().map(|line| format!("{}\n", line)).collect::<String>();
+
+    let lines = input.lines().map(String::from).collect::<Vec<String>>();
+    let mut lines = minimize_units(lines, |lines| still_fails(&join_lines(lines)));
+
+    let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let keep_columns = |columns: &[usize]| {
+        lines
+            .iter()
+            .map(|line| line.chars().enumerate().filter(|(column, _)| columns.contains(column)).map(|(_, c)| c).collect::<String>())
+            .collect::<Vec<String>>()
+    };
+    let columns = minimize_units((0..width).collect(), |columns| still_fails(&join_lines(&keep_columns(columns))));
+    lines = keep_columns(&columns);
+
+    for index in 0..lines.len() {
+        let characters = lines[index].chars().collect::<Vec<char>>();
+        let characters = minimize_units(characters, |characters| {
+            let mut candidate = lines.clone();
+            candidate[index] = characters.iter().collect();
+            return still_fails(&join_lines(&candidate));
+        });
+        lines[index] = characters.into_iter().collect();
+    }
+
+    return join_lines(&lines);
+}
+
+// Remove as many runs of units as possible while `still_fails` holds for those left.
+fn minimize_units<T: Clone>(mut units: Vec<T>, still_fails: impl Fn(&[T]) -> bool) -> Vec<T> {
+    let mut chunk_size = units.len() / 2;
+    while chunk_size > 0 {
+        let mut removed_any = false;
+        let mut start = 0;
+        while start < units.len() {
+            let end = (start + chunk_size).min(units.len());
+            let candidate = units[..start].iter().chain(&units[end..]).cloned().collect::<Vec<T>>();
+            if !candidate.is_empty() && still_fails(&candidate) {
+                units = candidate;
+                removed_any = true;
+            } else {
+                start = end;
+            }
+        }
+        // retry the same size after a removal, since units that were needed before may not be any more
+        if !removed_any {
+            chunk_size /= 2;
+        }
+        chunk_size = chunk_size.min(units.len() / 2);
+    }
+    return units;
+}
+
+// Print whether each part's implementations agreed and, if not, the minimized input
+// they disagree on.
+pub fn print_crosschecks(crosschecks: &[Crosscheck]) {
+    for crosscheck in crosschecks {
+        let selectors = crosscheck.implementations.iter().map(|solution| solution.selector()).collect::<Vec<String>>();
+        let width = selectors.iter().map(String::len).max().unwrap_or(0);
+        let disagreement = match &crosscheck.disagreement {
+            Some(disagreement) => disagreement,
+            None => {
+                println!("PASS     {}  {} agree on {} inputs", selectors[0], selectors.join(", "), crosscheck.inputs_checked);
+                continue;
+            }
+        };
+
+        println!("FAIL     {}  {} disagree on {}", selectors[0], selectors.join(", "), disagreement.input_name);
+        for (selector, outcome) in selectors.iter().zip(&disagreement.outcomes) {
+            println!("           {:<width$}  {}", selector, outcome, width = width);
+        }
+        let line_count = disagreement.minimized_input.lines().count();
+        println!("         minimized input ({} line{}):", line_count, if line_count == 1 { "" } else { "s" });
+        for line in disagreement.minimized_input.lines() {
+            println!("           | {}", line);
+        }
+        println!("         on which:");
+        for (selector, outcome) in selectors.iter().zip(&disagreement.minimized_outcomes) {
+            println!("           {:<width$}  {}", selector, outcome, width = width);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+// Puzzle inputs baked into the binary at compile time, so that it can run without the
+// repository checkout. Only populated when built with the `embed-inputs` feature.
+include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+// Whether this binary was built with its inputs embedded.
+pub fn is_available() -> bool {
+    cfg!(feature = "embed-inputs")
+}
+
+// The contents of an embedded file from `problems/`, by file name (e.g. "03.txt").
+pub fn embedded_input(file_name: &str) -> Option<&'static str> {
+    EMBEDDED_INPUTS.iter().find(|(name, _)| *name == file_name).map(|(_, contents)| *contents)
+}
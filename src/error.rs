@@ -0,0 +1,151 @@
+use std::fmt;
+use std::io;
+use std::num::TryFromIntError;
+use std::time::Duration;
+
+use crate::file::display_path;
+
+// Every error a solution can produce.
+//
+// Parse errors carry enough context to point at the offending input: the 1-indexed
+// line and column, the text of the line, and a description of what was expected
+// there. The file is attached afterwards by whoever knows which file was read.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse {
+        file: Option<String>,
+        line: usize,
+        column: usize,
+        snippet: String,
+        expected: String,
+    },
+    Validation(String),
+    Internal(String),
+    // A solver gave up because it was asked to (see `cancel::check`)
+    Cancelled,
+    // A solver didn't finish within its time limit
+    Timeout(Duration),
+}
+
+impl Error {
+    // Build a parse error pointing at `byte_offset` within `line`, which is the
+    // 0-indexed line `line_index` of the input.
+    pub fn parse(line_index: usize, line: &str, byte_offset: usize, expected: impl Into<String>) -> Self {
+        let byte_offset = byte_offset.min(line.len());
+        Error::Parse {
+            file: None,
+            line: line_index + 1,
+            column: line[..byte_offset].chars().count() + 1,
+            snippet: line.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    // Build a parse error pointing at `fragment`, which must be a slice of `line`.
+    pub fn parse_at(line_index: usize, line: &str, fragment: &str, expected: impl Into<String>) -> Self {
+        Error::parse(line_index, line, offset_within(line, fragment), expected)
+    }
+
+    // A short, stable name for the category of error, for machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "io",
+            Error::Parse { .. } => "parse",
+            Error::Validation(_) => "validation",
+            Error::Internal(_) => "internal",
+            Error::Cancelled => "cancelled",
+            Error::Timeout(_) => "timeout",
+        }
+    }
+
+    // Record which file the error came from, so that it can be reported to the user.
+    pub fn in_file(self, path: &str) -> Self {
+        let path = display_path(path);
+        match self {
+            Error::Parse { file: None, line, column, snippet, expected } => Error::Parse {
+                file: Some(path.to_string()),
+                line,
+                column,
+                snippet,
+                expected,
+            },
+            Error::Io(err) => Error::Io(io::Error::new(err.kind(), format!("{}: {}", path, err))),
+            other => other,
+        }
+    }
+}
+
+// The byte offset of `fragment` within `outer`. `fragment` must have been sliced from
+// `outer` (e.g. via `split` or `trim`); otherwise the start of `outer` is returned.
+pub fn offset_within(outer: &str, fragment: &str) -> usize {
+    let outer_start = outer.as_ptr() as usize;
+    let fragment_start = fragment.as_ptr() as usize;
+    if fragment_start >= outer_start && fragment_start <= outer_start + outer.len() {
+        fragment_start - outer_start
+    } else {
+        0
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Parse { file, line, column, snippet, expected } => {
+                match file {
+                    Some(file) => writeln!(f, "Parse error at {}:{}:{} - expected {}", file, line, column, expected)?,
+                    None => writeln!(f, "Parse error at line {}, column {} - expected {}", line, column, expected)?,
+                }
+                let gutter = line.to_string();
+                writeln!(f, "{} | {}", gutter, snippet)?;
+                write!(f, "{} | {}^", " ".repeat(gutter.len()), " ".repeat(column - 1))
+            }
+            Error::Validation(message) => write!(f, "Invalid input: {}", message),
+            Error::Internal(message) => write!(f, "Internal error: {}", message),
+            Error::Cancelled => write!(f, "Cancelled before finishing"),
+            Error::Timeout(limit) => write!(f, "Timed out after {:?}", limit),
+        }
+    }
+}
+
+// I/O errors can't be cloned, so a clone keeps only the kind and message of one.
+impl Clone for Error {
+    fn clone(&self) -> Self {
+        match self {
+            Error::Io(err) => Error::Io(io::Error::new(err.kind(), err.to_string())),
+            Error::Parse { file, line, column, snippet, expected } => Error::Parse {
+                file: file.clone(),
+                line: *line,
+                column: *column,
+                snippet: snippet.clone(),
+                expected: expected.clone(),
+            },
+            Error::Validation(message) => Error::Validation(message.clone()),
+            Error::Internal(message) => Error::Internal(message.clone()),
+            Error::Cancelled => Error::Cancelled,
+            Error::Timeout(limit) => Error::Timeout(*limit),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(err: TryFromIntError) -> Self {
+        Error::Internal(format!("integer conversion failed: {}", err))
+    }
+}
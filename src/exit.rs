@@ -0,0 +1,69 @@
+use std::process::ExitCode;
+
+use crate::error::Error;
+use crate::runner::RunResult;
+use crate::verify::{Verdict, Verification};
+
+// The exit status of the runner, so that scripts can tell kinds of failure apart
+// without reading stderr. These are documented in the README and the usage message,
+// so existing codes must not change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    Success = 0,
+    // The command line was invalid: an unknown command or option, a bad selector, or
+    // a missing or unexpected argument
+    Usage = 2,
+    // A file couldn't be read or written (e.g. a missing input), or a download failed
+    Io = 3,
+    // An input (or answers, ledger or config file) was malformed
+    Parse = 4,
+    // A solver failed on its input
+    Solver = 5,
+    // `verify` found an answer that doesn't match the recorded one, or `crosscheck`
+    // found implementations of a part that disagree
+    Mismatch = 6,
+    // A solver didn't finish within its time limit
+    Timeout = 7,
+}
+
+impl Exit {
+    pub fn for_error(err: &Error) -> Exit {
+        match err {
+            Error::Io(_) => Exit::Io,
+            Error::Parse { .. } => Exit::Parse,
+            Error::Validation(_) | Error::Internal(_) => Exit::Solver,
+            Error::Cancelled | Error::Timeout(_) => Exit::Timeout,
+        }
+    }
+
+    // Success if every run succeeded, otherwise the status for the first failed run in
+    // the order given.
+    pub fn for_results(results: &[RunResult]) -> Exit {
+        for result in results {
+            if let Err(err) = &result.answer {
+                return Exit::for_error(err);
+            }
+        }
+        return Exit::Success;
+    }
+
+    // Success if every recorded answer was reproduced. Otherwise a mismatch, unless the
+    // first failed part couldn't be run at all, in which case the status says why.
+    pub fn for_verifications(verifications: &[Verification]) -> Exit {
+        for verification in verifications {
+            if let Verdict::Fail { .. } = verification.verdict {
+                match &verification.result {
+                    Some(RunResult { answer: Err(err), .. }) => return Exit::for_error(err),
+                    _ => return Exit::Mismatch,
+                }
+            }
+        }
+        return Exit::Success;
+    }
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> ExitCode {
+        ExitCode::from(exit as u8)
+    }
+}
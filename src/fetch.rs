@@ -0,0 +1,269 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+
+// The site hosting this year's puzzles. Only plain HTTP is supported, so against the
+// real site this needs to point at a local HTTPS-terminating proxy.
+pub const DEFAULT_BASE_URL: &str = "http://adventofcode.com/2023";
+// Settings for fetching, as "key = value" lines: `session`, `base_url` and `user_agent`.
+pub const DEFAULT_CONFIG_FILE: &str = ".aoc-config";
+// Environment variables that take precedence over the config file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"), " (puzzle input fetcher)");
+// The minimum time between two requests to the server, including across runs.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+// Where the time of the last request is kept, within the input directory.
+const LAST_FETCH_FILE: &str = ".last-fetch";
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub struct FetchConfig {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub user_agent: String,
+    pub min_interval: Duration,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        FetchConfig {
+            base_url: String::from(DEFAULT_BASE_URL),
+            session: None,
+            user_agent: String::from(DEFAULT_USER_AGENT),
+            min_interval: DEFAULT_MIN_INTERVAL,
+        }
+    }
+}
+
+impl FetchConfig {
+    // Load the config file at `path` (a missing file means the defaults), then apply
+    // any overrides from the environment.
+    pub fn load(path: &str) -> Result<FetchConfig, Error> {
+        let mut config = match fs::read_to_string(path) {
+            Ok(contents) => FetchConfig::parse(&contents).map_err(|err| err.in_file(path))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => FetchConfig::default(),
+            Err(err) => return Err(Error::from(err).in_file(path)),
+        };
+        if let Ok(session) = env::var(SESSION_VAR) {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var(BASE_URL_VAR) {
+            config.base_url = base_url;
+        }
+
+        return Ok(config);
+    }
+
+    pub fn parse(contents: &str) -> Result<FetchConfig, Error> {
+        let mut config = FetchConfig::default();
+        for (line_index, line) in contents.lines().enumerate() {
+            let trimmed_line = line.trim();
+            if trimmed_line.is_empty() || trimmed_line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match trimmed_line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim().to_string()),
+                None => return Err(Error::parse(line_index, line, line.len(), "a setting of the form 'key = value'")),
+            };
+            match key {
+                "session" => config.session = Some(value),
+                "base_url" => config.base_url = value,
+                "user_agent" => config.user_agent = value,
+                _ => return Err(Error::parse_at(line_index, line, key, "one of 'session', 'base_url' or 'user_agent'")),
+            }
+        }
+
+        return Ok(config);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    // The input was already on disk, so no request was made
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+// Make sure the input for `day` is cached as `<input_dir>/NN.txt`, downloading it if
+// it isn't. An empty file (as created by `new-day`) doesn't count as cached.
+pub fn fetch_input(config: &FetchConfig, input_dir: &Path, day: u32) -> Result<Fetched, Error> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::Validation(format!("day must be between 1 and 25, got {}", day)));
+    }
+    let input_path = input_dir.join(format!("{:02}.txt", day));
+    if fs::metadata(&input_path).is_ok_and(|metadata| metadata.len() > 0) {
+        return Ok(Fetched::Cached(input_path));
+    }
+    let session = match &config.session {
+        Some(session) => session,
+        None => {
+            return Err(Error::Validation(format!(
+                "no session token - set {} or add 'session = <token>' to {}",
+                SESSION_VAR, DEFAULT_CONFIG_FILE
+            )))
+        }
+    };
+
+    let url = format!("{}/day/{}/input", config.base_url.trim_end_matches('/'), day);
+    parse_url(&url)?;
+
+    fs::create_dir_all(input_dir)?;
+    wait_for_rate_limit(&input_dir.join(LAST_FETCH_FILE), config.min_interval)?;
+    let response = http_get(&url, session, &config.user_agent)?;
+    match response.status {
+        200 if !response.body.is_empty() => {}
+        200 => return Err(download_error(format!("{} returned an empty input", url))),
+        404 => return Err(download_error(format!("day {}'s input isn't available from {} (404) - has the puzzle unlocked yet?", day, url))),
+        301 | 302 | 303 | 307 | 308 => {
+            let location = response.header("location").unwrap_or("an unknown location");
+            return Err(download_error(format!("{} redirected to {} - only plain HTTP is supported", url, location)));
+        }
+        status => {
+            let reason = String::from_utf8_lossy(&response.body).lines().next().unwrap_or("").trim().to_string();
+            return Err(download_error(format!("{} responded with status {}: {}", url, status, reason)));
+        }
+    }
+    let input = match String::from_utf8(response.body) {
+        Ok(input) => input,
+        Err(_) => return Err(download_error(format!("{} returned an input that isn't valid UTF-8", url))),
+    };
+
+    // write to a temporary file first, so that a failed write never looks like a cached input
+    let partial_path = input_dir.join(format!(".{:02}.txt.partial", day));
+    fs::write(&partial_path, input)?;
+    fs::rename(&partial_path, &input_path)?;
+
+    return Ok(Fetched::Downloaded(input_path));
+}
+
+// Sleep until at least `min_interval` has passed since the last request recorded in
+// `state_file`, then record a new request as happening now.
+fn wait_for_rate_limit(state_file: &Path, min_interval: Duration) -> Result<(), Error> {
+    let last_fetch = fs::read_to_string(state_file).ok().and_then(|contents| contents.trim().parse::<u64>().ok());
+    if let Some(last_fetch) = last_fetch {
+        let since_last_fetch = Duration::from_millis(millis_since_epoch(false).saturating_sub(last_fetch));
+        if since_last_fetch < min_interval {
+            thread::sleep(min_interval - since_last_fetch);
+        }
+    }
+    // rounding the recorded time up (and the current time down, above) means the
+    // interval can only be overestimated, never cut short
+    fs::write(state_file, millis_since_epoch(true).to_string())?;
+
+    return Ok(());
+}
+
+fn millis_since_epoch(round_up: bool) -> u64 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let millis = if round_up { nanos.div_ceil(1_000_000) } else { nanos / 1_000_000 };
+    return u64::try_from(millis).unwrap_or(u64::MAX);
+}
+
+struct Response {
+    status: u32,
+    // header names are lowercased
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str())
+    }
+}
+
+// Split an "http://host[:port][/path]" URL into its host, port and path.
+fn parse_url(url: &str) -> Result<(&str, u16, &str), Error> {
+    let rest = match url.strip_prefix("http://") {
+        Some(rest) => rest,
+        None => return Err(Error::Validation(format!("unsupported URL '{}' - only plain http:// URLs are supported", url))),
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    match authority.split_once(':') {
+        Some((host, port)) => match port.parse::<u16>() {
+            Ok(port) => return Ok((host, port, path)),
+            Err(_) => return Err(Error::Validation(format!("invalid port '{}' in URL '{}'", port, url))),
+        },
+        None => return Ok((authority, 80, path)),
+    }
+}
+
+fn http_get(url: &str, session: &str, user_agent: &str) -> Result<Response, Error> {
+    let (host, port, path) = parse_url(url)?;
+    let mut stream = TcpStream::connect((host, port)).map_err(|err| io::Error::new(err.kind(), format!("{}: {}", url, err)))?;
+    stream.set_read_timeout(Some(NETWORK_TIMEOUT))?;
+    stream.set_write_timeout(Some(NETWORK_TIMEOUT))?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\nAccept: text/plain\r\nConnection: close\r\n\r\n",
+        path, host, user_agent, session
+    );
+    stream.write_all(request.as_bytes())?;
+    let mut raw_response = Vec::new();
+    stream.read_to_end(&mut raw_response)?;
+
+    return parse_response(&raw_response).ok_or_else(|| download_error(format!("{} returned a malformed HTTP response", url)));
+}
+
+// A download that failed once the server was reached is still an I/O failure, as
+// opposed to a problem with the request itself (such as a missing session token).
+fn download_error(message: String) -> Error {
+    return Error::Io(io::Error::other(message));
+}
+
+fn parse_response(raw_response: &[u8]) -> Option<Response> {
+    let header_end = raw_response.windows(4).position(|window| window == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&raw_response[..header_end]).ok()?;
+    let mut body = raw_response[header_end + 4..].to_vec();
+
+    let mut lines = head.split("\r\n");
+    // e.g. "HTTP/1.1 200 OK"
+    let status = lines.next()?.split_whitespace().nth(1)?.parse::<u32>().ok()?;
+    let mut headers = Vec::new();
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+    let response = Response { status, headers, body: Vec::new() };
+
+    if response.header("transfer-encoding").is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked")) {
+        body = decode_chunked(&body)?;
+    } else if let Some(length) = response.header("content-length") {
+        // a shorter body means the connection dropped partway through the download
+        let length = length.parse::<usize>().ok()?;
+        if body.len() < length {
+            return None;
+        }
+        body.truncate(length);
+    }
+
+    return Some(Response { body, ..response });
+}
+
+// Decode a body sent with "Transfer-Encoding: chunked": a series of chunks, each a hex
+// length line followed by that many bytes, ending with a zero-length chunk.
+fn decode_chunked(mut encoded: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = encoded.windows(2).position(|window| window == b"\r\n")?;
+        let size_line = std::str::from_utf8(&encoded[..line_end]).ok()?;
+        // chunk extensions after a ';' are allowed, and ignored
+        let size = usize::from_str_radix(size_line.split(';').next()?.trim(), 16).ok()?;
+        encoded = &encoded[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(encoded.get(..size)?);
+        encoded = encoded.get(size + 2..)?;
+    }
+}
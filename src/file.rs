@@ -1,11 +1,139 @@
 use std::fs::File;
 use std::io::{self, Read};
+use std::path::Path;
+use std::sync::OnceLock;
 
-pub fn read_file(file_path: &str) -> io::Result<String> {
-    let mut file = File::open(file_path)?;
+use crate::embedded::embedded_input;
+use crate::solution::Part;
+
+pub const DEFAULT_INPUT_DIR: &str = "problems";
+
+// The input path that means "read from stdin" rather than from a file.
+pub const STDIN_PATH: &str = "-";
+
+// Input paths starting with this refer to files embedded in the binary, by their file
+// name within `problems/` (e.g. "embedded:03.txt").
+pub const EMBEDDED_PREFIX: &str = "embedded:";
+
+// Read an input from wherever its path points: a file embedded in the binary, stdin,
+// or the filesystem.
+pub fn read_input(input_path: &str) -> io::Result<String> {
+    match input_path.strip_prefix(EMBEDDED_PREFIX) {
+        Some(file_name) => match embedded_input(file_name) {
+            Some(contents) => return Ok(contents.to_string()),
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "not embedded in this binary")),
+        },
+        None => return read_file(input_path),
+    }
+}
 
+// Everything read from stdin (or why it couldn't be read), which is kept since stdin
+// can only be read once but several groups of parts may need it.
+static STDIN_CONTENTS: OnceLock<Result<String, (io::ErrorKind, String)>> = OnceLock::new();
+
+pub fn read_file(file_path: &str) -> io::Result<String> {
     let mut contents = String::new();
+    if file_path == STDIN_PATH {
+        let stdin = STDIN_CONTENTS.get_or_init(|| {
+            let mut contents = String::new();
+            match io::stdin().read_to_string(&mut contents) {
+                Ok(_) => Ok(contents),
+                Err(err) => Err((err.kind(), err.to_string())),
+            }
+        });
+        match stdin {
+            Ok(contents) => return Ok(contents.clone()),
+            Err((kind, message)) => return Err(io::Error::new(*kind, message.clone())),
+        }
+    }
+
+    let mut file = File::open(file_path)?;
     file.read_to_string(&mut contents)?;
 
     Ok(contents)
-}
\ No newline at end of file
+}
+
+// A name for the input path suitable for messages, e.g. "<stdin>" for `STDIN_PATH`.
+pub fn display_path(file_path: &str) -> &str {
+    if file_path == STDIN_PATH {
+        "<stdin>"
+    } else {
+        file_path
+    }
+}
+
+// Whether stdin has input piped or redirected into it (as opposed to being a terminal,
+// or closed as it often is for scheduled jobs), so that it can be read as puzzle input.
+#[cfg(unix)]
+pub fn stdin_is_piped() -> bool {
+    use std::os::fd::AsFd;
+    use std::os::unix::fs::FileTypeExt;
+
+    let stdin = match io::stdin().as_fd().try_clone_to_owned() {
+        Ok(fd) => File::from(fd),
+        Err(_) => return false,
+    };
+    match stdin.metadata() {
+        Ok(metadata) => metadata.file_type().is_fifo() || metadata.file_type().is_file(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+pub fn stdin_is_piped() -> bool {
+    use std::io::IsTerminal;
+
+    !io::stdin().is_terminal()
+}
+
+// Where to look for puzzle inputs when no input file is given explicitly. Inputs
+// follow the convention `<dir>/NN.txt` for the real input and `<dir>/NNexample.txt`
+// for the example, where a day whose parts use different examples has
+// `<dir>/NNexample-a.txt` and `<dir>/NNexample-b.txt` instead. With `embedded`, the
+// same files are taken from those embedded in the binary instead of from `dir`.
+#[derive(Debug, Clone)]
+pub struct InputOptions {
+    pub dir: String,
+    pub example: bool,
+    pub embedded: bool,
+}
+
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions {
+            dir: String::from(DEFAULT_INPUT_DIR),
+            example: false,
+            embedded: false,
+        }
+    }
+}
+
+impl InputOptions {
+    // The input file to use for the given day and part.
+    pub fn resolve(&self, day: u32, part: Part) -> String {
+        if !self.example {
+            return self.path(&format!("{:02}.txt", day));
+        }
+
+        let per_part_example = format!("{:02}example-{}.txt", day, part);
+        if self.exists(&per_part_example) {
+            return self.path(&per_part_example);
+        }
+        return self.path(&format!("{:02}example.txt", day));
+    }
+
+    // The path to a file in the input directory, or to the embedded copy of it.
+    pub fn path(&self, file_name: &str) -> String {
+        if self.embedded {
+            return format!("{}{}", EMBEDDED_PREFIX, file_name);
+        }
+        return format!("{}/{}", self.dir, file_name);
+    }
+
+    fn exists(&self, file_name: &str) -> bool {
+        if self.embedded {
+            return embedded_input(file_name).is_some();
+        }
+        return Path::new(&self.dir).join(file_name).exists();
+    }
+}
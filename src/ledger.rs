@@ -0,0 +1,270 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::answers::Answers;
+use crate::error::Error;
+use crate::file::{read_file, InputOptions};
+use crate::runner::RunResult;
+
+pub const DEFAULT_LEDGER_FILE: &str = "problems/guesses.txt";
+
+// What the puzzle site said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feedback {
+    TooHigh,
+    TooLow,
+    Wrong,
+    Correct,
+}
+
+impl Feedback {
+    // Accepts e.g. "too high", "too-high" or "Too_High".
+    pub fn parse(text: &str) -> Option<Feedback> {
+        match text.trim().to_ascii_lowercase().replace(['-', '_'], " ").as_str() {
+            "too high" => Some(Feedback::TooHigh),
+            "too low" => Some(Feedback::TooLow),
+            "wrong" => Some(Feedback::Wrong),
+            "correct" => Some(Feedback::Correct),
+            _ => None,
+        }
+    }
+
+    // The single-word form used in the ledger file.
+    fn token(&self) -> &'static str {
+        match self {
+            Feedback::TooHigh => "too-high",
+            Feedback::TooLow => "too-low",
+            Feedback::Wrong => "wrong",
+            Feedback::Correct => "correct",
+        }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.token().replace('-', " "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+    pub selector: String,
+    pub answer: String,
+    pub feedback: Feedback,
+}
+
+// Why a computed answer is known not to be right, based on earlier guesses.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Warning {
+    // This exact answer was already submitted and rejected
+    KnownWrong(Feedback),
+    // The answer is at least as high as a guess that was too high
+    AboveBound(String),
+    // The answer is at most as low as a guess that was too low
+    BelowBound(String),
+    // A different answer is known to be correct
+    NotCorrect(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::KnownWrong(feedback) => write!(f, "was already guessed and was {}", feedback),
+            Warning::AboveBound(bound) => write!(f, "is too high - {} was already too high", bound),
+            Warning::BelowBound(bound) => write!(f, "is too low - {} was already too low", bound),
+            Warning::NotCorrect(correct) => write!(f, "is wrong - {} is known to be correct", correct),
+        }
+    }
+}
+
+// What recording a guess revealed about the answer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum GuessOutcome {
+    // The guess was correct and was added to the answers file
+    Correct,
+    // The guess was wrong; these are the tightest known bounds on the answer, as from
+    // `Ledger::bounds`
+    Wrong { lower: Option<i128>, upper: Option<i128> },
+}
+
+// Every answer submitted to the puzzle site and what it said about them, so that
+// known-bad answers aren't submitted again. On disk this is a plain text file with one
+// "<selector> <feedback> <answer>" line per guess (e.g. "5a too-high 12345"); blank
+// lines and lines starting with '#' are ignored, but kept when the ledger is saved.
+#[derive(Debug, Default)]
+pub struct Ledger {
+    guesses: Vec<Guess>,
+    // every line of the file, and which of them holds each guess
+    lines: Vec<String>,
+    guess_lines: Vec<usize>,
+}
+
+impl Ledger {
+    // Load the ledger at `path`. A missing file is treated as having no guesses.
+    pub fn load(path: &str) -> Result<Ledger, Error> {
+        let contents = match read_file(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Ledger::default()),
+            Err(err) => return Err(Error::from(err).in_file(path)),
+        };
+        Ledger::parse(&contents).map_err(|err| err.in_file(path))
+    }
+
+    pub fn parse(contents: &str) -> Result<Ledger, Error> {
+        let mut guesses = Vec::new();
+        let mut guess_lines = Vec::new();
+        for (line_index, line) in contents.lines().enumerate() {
+            let trimmed_line = line.trim();
+            if trimmed_line.is_empty() || trimmed_line.starts_with('#') {
+                continue;
+            }
+            let mut fields = trimmed_line.splitn(3, char::is_whitespace);
+            let (selector, feedback, answer) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(selector), Some(feedback), Some(answer)) if !answer.trim().is_empty() => (selector, feedback, answer.trim()),
+                _ => return Err(Error::parse(line_index, line, line.len(), "a selector, feedback and answer, e.g. '5a too-high 12345'")),
+            };
+            let feedback = match Feedback::parse(feedback) {
+                Some(feedback) => feedback,
+                None => return Err(Error::parse_at(line_index, line, feedback, "one of 'too-high', 'too-low', 'wrong' or 'correct'")),
+            };
+            guesses.push(Guess { selector: selector.to_string(), answer: answer.to_string(), feedback });
+            guess_lines.push(line_index);
+        }
+
+        Ok(Ledger { guesses, lines: contents.lines().map(String::from).collect(), guess_lines })
+    }
+
+    pub fn guesses(&self, selector: &str) -> impl Iterator<Item = &Guess> {
+        let selector = selector.to_string();
+        self.guesses.iter().filter(move |guess| guess.selector == selector)
+    }
+
+    // Record a guess, replacing the feedback for any earlier guess of the same answer
+    // (on the same line, when saved).
+    pub fn record(&mut self, guess: Guess) {
+        let line = format!("{} {} {}", guess.selector, guess.feedback.token(), guess.answer);
+        let existing = self.guesses.iter().position(|existing| existing.selector == guess.selector && existing.answer == guess.answer);
+        match existing {
+            Some(index) => {
+                self.guesses[index].feedback = guess.feedback;
+                self.lines[self.guess_lines[index]] = line;
+            }
+            None => {
+                self.guesses.push(guess);
+                self.guess_lines.push(self.lines.len());
+                self.lines.push(line);
+            }
+        }
+    }
+
+    // Record a guess and save the ledger to `path`. A correct answer is also added to
+    // the answers file at `answers_path`, so that `verify` checks it from then on.
+    pub fn record_guess(&mut self, guess: Guess, path: &str, answers_path: &str) -> Result<GuessOutcome, Error> {
+        let selector = guess.selector.clone();
+        let answer = guess.answer.clone();
+        let feedback = guess.feedback;
+        self.record(guess);
+        self.save(path)?;
+
+        if feedback != Feedback::Correct {
+            let (lower, upper) = self.bounds(&selector);
+            return Ok(GuessOutcome::Wrong { lower, upper });
+        }
+        let mut answers = Answers::load(answers_path)?;
+        answers.insert(&selector, &answer);
+        answers.save(answers_path)?;
+        return Ok(GuessOutcome::Correct);
+    }
+
+    // The tightest known bounds on an integer answer: the highest guess that was too
+    // low and the lowest guess that was too high, both exclusive.
+    pub fn bounds(&self, selector: &str) -> (Option<i128>, Option<i128>) {
+        let mut lower: Option<i128> = None;
+        let mut upper: Option<i128> = None;
+        for guess in self.guesses(selector) {
+            let value = match guess.answer.parse::<i128>() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            match guess.feedback {
+                Feedback::TooLow => lower = Some(lower.map_or(value, |lower| lower.max(value))),
+                Feedback::TooHigh => upper = Some(upper.map_or(value, |upper| upper.min(value))),
+                _ => {}
+            }
+        }
+        return (lower, upper);
+    }
+
+    // Check an answer for `selector` against what earlier guesses revealed.
+    pub fn check(&self, selector: &str, answer: &str) -> Option<Warning> {
+        for guess in self.guesses(selector) {
+            match guess.feedback {
+                Feedback::Correct if guess.answer == answer => return None,
+                Feedback::Correct => return Some(Warning::NotCorrect(guess.answer.clone())),
+                feedback if guess.answer == answer => return Some(Warning::KnownWrong(feedback)),
+                _ => {}
+            }
+        }
+
+        let value = match answer.parse::<i128>() {
+            Ok(value) => value,
+            Err(_) => return None,
+        };
+        match self.bounds(selector) {
+            (_, Some(upper)) if value >= upper => return Some(Warning::AboveBound(upper.to_string())),
+            (Some(lower), _) if value <= lower => return Some(Warning::BelowBound(lower.to_string())),
+            _ => return None,
+        }
+    }
+
+    // Write the ledger back to `path`: the lines it was loaded from (comments included),
+    // with any new guesses added at the end in the order they were made.
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let contents = self.lines.iter().map(|line| format!("{}\n", line)).collect::<String>();
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).map_err(|err| Error::from(err).in_file(path))?;
+            }
+        }
+        fs::write(path, contents).map_err(|err| Error::from(err).in_file(path))
+    }
+}
+
+// Warn on stderr about every computed answer that the guesses in the ledger at `path`
+// show to be wrong. Guesses are only about the real inputs, so results for other
+// inputs are skipped. A ledger that can't be read shouldn't stop the answers from
+// being reported, so that only warns too.
+pub fn warn_about_known_guesses(path: &str, results: &[RunResult], input_options: &InputOptions) {
+    let ledger = match Ledger::load(path) {
+        Ok(ledger) => ledger,
+        Err(err) => {
+            eprintln!("Warning: could not check earlier guesses: {}", err);
+            return;
+        }
+    };
+    for result in results {
+        if input_options.example || result.input_file != input_options.resolve(result.solution.day(), result.solution.part()) {
+            continue;
+        }
+        if let Ok(solved) = &result.answer {
+            let selector = result.solution.selector();
+            if let Some(warning) = ledger.check(&selector, &solved.answer.to_string()) {
+                eprintln!("Warning: the answer to {} ({}) {}", selector, solved.answer, warning);
+            }
+        }
+    }
+}
+
+// Print what recording a guess for `selector` revealed: where a correct answer was
+// saved, or what is now known about the answer.
+pub fn print_guess_outcome(selector: &str, answer: &str, outcome: &GuessOutcome, answers_path: &str) {
+    match outcome {
+        GuessOutcome::Correct => println!("Added {} {} to {}", selector, answer, answers_path),
+        GuessOutcome::Wrong { lower: Some(lower), upper: Some(upper) } => println!("The answer to {} is between {} and {}", selector, lower, upper),
+        GuessOutcome::Wrong { lower: Some(lower), upper: None } => println!("The answer to {} is above {}", selector, lower),
+        GuessOutcome::Wrong { lower: None, upper: Some(upper) } => println!("The answer to {} is below {}", selector, upper),
+        GuessOutcome::Wrong { lower: None, upper: None } => {}
+    }
+}
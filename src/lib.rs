@@ -0,0 +1,26 @@
+#![allow(clippy::needless_return)]
+
+// Solutions for the 2023 Advent of Code. Each day lives in `problems::problemNN` and
+// exposes its parsers, data models and solve functions; `problems::SOLUTIONS` lists
+// every part that the runner knows how to solve.
+
+pub mod answers;
+pub mod bench;
+pub mod cancel;
+pub mod crosscheck;
+pub mod embedded;
+pub mod error;
+pub mod exit;
+pub mod fetch;
+pub mod file;
+pub mod json;
+pub mod ledger;
+pub mod memory;
+pub mod problems;
+pub mod progress;
+pub mod rng;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod verify;
+pub mod watch;
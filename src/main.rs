@@ -1,56 +1,548 @@
+#![allow(clippy::needless_return)]
+
 use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
 use std::process::ExitCode;
+use std::time::Duration;
+
+use advent_of_code_2023::answers::{Answers, DEFAULT_ANSWERS_FILE, DEFAULT_EXAMPLE_ANSWERS_FILE};
+use advent_of_code_2023::bench::{bench_solution, BenchConfig};
+use advent_of_code_2023::crosscheck::run_crosschecks;
+use advent_of_code_2023::fetch::{fetch_input, FetchConfig, Fetched, DEFAULT_CONFIG_FILE};
+use advent_of_code_2023::embedded;
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::exit::Exit;
+use advent_of_code_2023::file::{display_path, read_input, stdin_is_piped, InputOptions, STDIN_PATH};
+use advent_of_code_2023::ledger::{print_guess_outcome, warn_about_known_guesses, Feedback, Guess, Ledger, DEFAULT_LEDGER_FILE};
+use advent_of_code_2023::problems::{find_solution, generate_input, select_solutions, selector_help, SOLUTIONS};
+use advent_of_code_2023::runner::{print_json_lines, print_result, print_summary_table, run_all, run_parallel, RunOptions};
+use advent_of_code_2023::scaffold::new_day;
+use advent_of_code_2023::solution::Solution;
+use advent_of_code_2023::verify::{print_verifications, verify_all};
+use advent_of_code_2023::watch::watch;
+
+// The parsed command line. Positional arguments are the problem selector or a
+// command (such as `all` or `bench`) followed by its arguments; flags may appear anywhere.
+struct Args {
+    positionals: Vec<String>,
+    time: bool,
+    watch: bool,
+    // don't show progress for slow parts on stderr
+    quiet: bool,
+    format: OutputFormat,
+    input: InputOptions,
+    // the timeout, and whether to show progress once it's known if stderr is a terminal
+    run: RunOptions,
+    // for generating inputs
+    seed: Option<u64>,
+    size: usize,
+}
+
+// How many records (or the width of the grid) to generate by default.
+const DEFAULT_GENERATE_SIZE: usize = 100;
 
-use crate::problems::problem01::{problem01_part_1, problem01_part_2};
-use crate::problems::problem02::{problem02_part_1, problem02_part_2};
-use crate::problems::problem03::{problem03_part_1, problem03_part_2};
-use crate::problems::problem04::{problem04_part_1, problem04_part_2};
-use crate::problems::problem05::{problem05_part_1, problem05_part_2};
+#[derive(PartialEq)]
+enum OutputFormat {
+    Text,
+    // one JSON object per line for each solved part
+    Json,
+}
 
-mod problems;
-mod file;
+fn parse_args(mut raw_args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut args = Args {
+        positionals: Vec::new(),
+        time: false,
+        watch: false,
+        quiet: false,
+        format: OutputFormat::Text,
+        input: InputOptions::default(),
+        run: RunOptions::default(),
+        seed: None,
+        size: DEFAULT_GENERATE_SIZE,
+    };
+
+    while let Some(arg) = raw_args.next() {
+        // options that take a value accept both "--option value" and "--option=value"
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| match inline_value.clone().or_else(|| raw_args.next()) {
+            Some(value) => Ok(value),
+            None => Err(format!("Option '{}' requires a value", name)),
+        };
+
+        match flag.as_str() {
+            "--time" => args.time = true,
+            "--watch" => args.watch = true,
+            "--quiet" => args.quiet = true,
+            "--example" => args.input.example = true,
+            "--embedded" => args.input.embedded = true,
+            "--input-dir" => args.input.dir = value("--input-dir")?,
+            "--seed" => {
+                let seed = value("--seed")?;
+                args.seed = Some(seed.parse::<u64>().map_err(|_| format!("Invalid seed '{}' - expected a number", seed))?);
+            }
+            "--timeout" => {
+                let timeout = value("--timeout")?;
+                args.run.timeout = match timeout.parse::<f64>() {
+                    Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Some(Duration::from_secs_f64(seconds)),
+                    _ => return Err(format!("Invalid timeout '{}' - expected a positive number of seconds", timeout)),
+                };
+            }
+            "--size" => {
+                let size = value("--size")?;
+                args.size = match size.parse::<usize>() {
+                    Ok(size) if size > 0 => size,
+                    _ => return Err(format!("Invalid size '{}' - expected a positive number", size)),
+                };
+            }
+            "--format" => {
+                args.format = match value("--format")?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("Unknown format '{}' - expected 'text' or 'json'", other)),
+                }
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option '{}'", flag)),
+            _ => args.positionals.push(arg.trim().to_string()),
+        }
+    }
+
+    if args.input.embedded && !embedded::is_available() {
+        return Err(String::from("Option '--embedded' requires a binary built with '--features embed-inputs'"));
+    }
+
+    return Ok(args);
+}
 
 fn main() -> ExitCode {
-    let lower_problem_bound = 1;
-    let upper_problem_bound = 5;
+    let mut args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("{}", usage());
+            return Exit::Usage.into();
+        }
+    };
+
+    // a progress line would only get in the way of stderr that's going to a file or pipe
+    args.run.progress = !args.quiet && io::stderr().is_terminal();
+
+    let command = match args.positionals.first() {
+        Some(command) => command.as_str(),
+        None => {
+            eprintln!("You must provide a valid problem as the first command line argument");
+            eprintln!("{}", usage());
+            return Exit::Usage.into();
+        }
+    };
+    match command {
+        "all" => return all_command(&args),
+        "bench" => return bench_command(&args.positionals[1..], &args),
+        "verify" => return verify_command(&args.positionals[1..], &args),
+        "new-day" => return new_day_command(&args.positionals[1..], &args),
+        "fetch" => return fetch_command(&args.positionals[1..], &args),
+        "guess" => return guess_command(&args.positionals[1..]),
+        "generate" => return generate_command(&args.positionals[1..], &args),
+        "crosscheck" => return crosscheck_command(&args.positionals[1..], &args),
+        problem => return solve_command(problem, &args.positionals[1..], &args),
+    }
+}
+
+// Solve the selected problems against the given input file, or their conventional
+// inputs if none is given. A single part prints its answer, several print a table.
+fn solve_command(selector: &str, rest: &[String], args: &Args) -> ExitCode {
+    let solutions = match select_solutions(selector) {
+        Ok(solutions) => solutions,
+        Err(err) => {
+            eprintln!("{}", err);
+            return Exit::Usage.into();
+        }
+    };
+    let jobs = match input_jobs(rest, &solutions, args) {
+        Some(jobs) => jobs,
+        None => return Exit::Usage.into(),
+    };
+
+    if args.watch {
+        // only returns if the inputs can't be watched, or the terminal can't be written to
+        match watch(&jobs, args.time, &args.run) {
+            Ok(()) => return Exit::Success.into(),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                return command_exit(&err).into();
+            }
+        }
+    }
 
-    let mut args = env::args().skip(1);
-    let problem_number = match args.next() {
+    let results = run_parallel(&jobs, &args.run);
+    match (&args.format, results.as_slice()) {
+        (OutputFormat::Text, [result]) => print_result(result, args.time),
+        (OutputFormat::Text, _) => print_summary_table(&results, args.time),
+        (OutputFormat::Json, _) => print_json_lines(&results),
+    }
+    warn_about_known_guesses(DEFAULT_LEDGER_FILE, &results, &args.input);
+    return Exit::for_results(&results).into();
+}
+
+// Run every registered problem against its conventional input and print a summary table.
+fn all_command(args: &Args) -> ExitCode {
+    let results = run_all(&args.input, &args.run);
+    match args.format {
+        OutputFormat::Text => print_summary_table(&results, args.time),
+        OutputFormat::Json => print_json_lines(&results),
+    }
+    warn_about_known_guesses(DEFAULT_LEDGER_FILE, &results, &args.input);
+    return Exit::for_results(&results).into();
+}
+
+// Repeatedly run a single problem against its input file and report timing statistics.
+fn bench_command(rest: &[String], args: &Args) -> ExitCode {
+    let problem = match rest.first() {
         Some(problem) => problem,
         None => {
-            eprintln!("You must provide a valid problem number ({}-{}) as the first command line argument", lower_problem_bound, upper_problem_bound);
-            return ExitCode::from(1);
+            eprintln!("You must provide a problem to benchmark, e.g. 'bench 3b'");
+            return Exit::Usage.into();
+        }
+    };
+    let solution = match lookup_solution(problem) {
+        Some(solution) => solution,
+        None => return Exit::Usage.into(),
+    };
+    let input_file = match input_jobs(&rest[1..], &[solution], args) {
+        Some(mut jobs) => jobs.remove(0).1,
+        None => return Exit::Usage.into(),
+    };
+    let input = match read_input(&input_file) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Error: {}: {}", display_path(&input_file), err);
+            return Exit::Io.into();
+        }
+    };
+
+    println!("Benchmarking {} (Day {}: {}, part {})", solution.selector(), solution.day(), solution.title(), solution.part());
+    match bench_solution(solution, &input, &BenchConfig::default()) {
+        Ok(stats) => {
+            println!("{}", stats);
+            return Exit::Success.into();
+        }
+        Err(err) => {
+            let err = err.in_file(&input_file);
+            eprintln!("Error: {}", err);
+            return Exit::for_error(&err).into();
+        }
+    }
+}
+
+// Check every recorded answer in the answers file against a fresh run of its solution.
+// With --example, the examples are checked against their answers from the puzzle text.
+fn verify_command(rest: &[String], args: &Args) -> ExitCode {
+    let default_answers_file = if args.input.example { DEFAULT_EXAMPLE_ANSWERS_FILE } else { DEFAULT_ANSWERS_FILE };
+    let answers_file = match rest {
+        // the embedded copy of the answers file, e.g. "embedded:answers.txt"
+        [] if args.input.embedded => match Path::new(default_answers_file).file_name() {
+            Some(file_name) => args.input.path(&file_name.to_string_lossy()),
+            None => default_answers_file.to_string(),
+        },
+        [] => default_answers_file.to_string(),
+        [file] => file.clone(),
+        [_, extra, ..] => {
+            eprintln!("Unexpected argument '{}'", extra);
+            return Exit::Usage.into();
+        }
+    };
+    let answers = match Answers::load(&answers_file) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return Exit::for_error(&err).into();
+        }
+    };
+
+    let verifications = verify_all(&answers, &args.input, &args.run);
+    print_verifications(&verifications);
+    return Exit::for_verifications(&verifications).into();
+}
+
+// Scaffold the module, registry entries and input files for a new day.
+fn new_day_command(rest: &[String], args: &Args) -> ExitCode {
+    let day = match rest {
+        [day] => match day.parse::<u32>() {
+            Ok(day) => day,
+            Err(_) => {
+                eprintln!("Invalid day '{}' - expected a number, e.g. 'new-day 6'", day);
+                return Exit::Usage.into();
+            }
+        },
+        [] => {
+            eprintln!("You must provide the day to create, e.g. 'new-day 6'");
+            return Exit::Usage.into();
+        }
+        [_, extra, ..] => {
+            eprintln!("Unexpected argument '{}'", extra);
+            return Exit::Usage.into();
+        }
+    };
+
+    match new_day(Path::new(""), Path::new(&args.input.dir), day) {
+        Ok(changed) => {
+            for path in changed {
+                println!("Wrote {}", path.display());
+            }
+            return Exit::Success.into();
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return command_exit(&err).into();
+        }
+    }
+}
+
+// Download the inputs for the given days into the input directory, skipping any that
+// are already there.
+fn fetch_command(rest: &[String], args: &Args) -> ExitCode {
+    if rest.is_empty() {
+        eprintln!("You must provide the day to fetch, e.g. 'fetch 6'");
+        return Exit::Usage.into();
+    }
+    let mut days = Vec::new();
+    for day in rest {
+        match day.parse::<u32>() {
+            Ok(day) => days.push(day),
+            Err(_) => {
+                eprintln!("Invalid day '{}' - expected a number, e.g. 'fetch 6'", day);
+                return Exit::Usage.into();
+            }
+        }
+    }
+    let config = match FetchConfig::load(DEFAULT_CONFIG_FILE) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return command_exit(&err).into();
         }
     };
-    let input_file = match args.next() {
-        Some(file) => file,
+
+    for day in days {
+        match fetch_input(&config, Path::new(&args.input.dir), day) {
+            Ok(Fetched::Cached(path)) => println!("Day {}: already cached in {}", day, path.display()),
+            Ok(Fetched::Downloaded(path)) => println!("Day {}: downloaded to {}", day, path.display()),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                return command_exit(&err).into();
+            }
+        }
+    }
+    return Exit::Success.into();
+}
+
+// Record an answer submitted to the puzzle site along with its feedback, prompting for
+// the feedback if it isn't given. A correct answer is also added to the answers file.
+fn guess_command(rest: &[String]) -> ExitCode {
+    let (selector, answer, feedback) = match rest {
+        [selector, answer] => (selector, answer, None),
+        [selector, answer, feedback @ ..] => (selector, answer, Some(feedback.join(" "))),
+        _ => {
+            eprintln!("You must provide the problem and the answer you guessed, e.g. 'guess 5a 12345 too high'");
+            return Exit::Usage.into();
+        }
+    };
+    let solution = match lookup_solution(selector) {
+        Some(solution) => solution,
+        None => return Exit::Usage.into(),
+    };
+    let selector = solution.selector();
+    let mut ledger = match Ledger::load(DEFAULT_LEDGER_FILE) {
+        Ok(ledger) => ledger,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return command_exit(&err).into();
+        }
+    };
+    if let Some(warning) = ledger.check(&selector, answer) {
+        eprintln!("Warning: {} for {} {}", answer, selector, warning);
+    }
+
+    let feedback = match feedback.or_else(prompt_for_feedback) {
+        Some(feedback) => feedback,
+        None => return Exit::Usage.into(),
+    };
+    let feedback = match Feedback::parse(&feedback) {
+        Some(feedback) => feedback,
         None => {
-            eprintln!("You must provide a valid filepath for the problem input as the second command line argument");
-            return ExitCode::from(1);
+            eprintln!("Invalid feedback '{}' - expected 'too high', 'too low', 'wrong' or 'correct'", feedback);
+            return Exit::Usage.into();
+        }
+    };
+
+    let guess = Guess { selector: selector.clone(), answer: answer.clone(), feedback };
+    let outcome = match ledger.record_guess(guess, DEFAULT_LEDGER_FILE, DEFAULT_ANSWERS_FILE) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return command_exit(&err).into();
         }
     };
+    println!("Recorded {} for {} as {} in {}", answer, selector, feedback, DEFAULT_LEDGER_FILE);
+    print_guess_outcome(&selector, answer, &outcome, DEFAULT_ANSWERS_FILE);
+    return Exit::Success.into();
+}
+
+fn prompt_for_feedback() -> Option<String> {
+    print!("What did the site say (too high, too low, wrong or correct)? ");
+    let _ = io::stdout().flush();
+    let mut feedback = String::new();
+    match io::stdin().read_line(&mut feedback) {
+        Ok(read) if read > 0 => return Some(feedback.trim().to_string()),
+        _ => {
+            eprintln!("\nNo feedback given - nothing was recorded");
+            return None;
+        }
+    }
+}
 
-    let result = match problem_number.trim() {
-        "1a" => problem01_part_1(&input_file),
-        "1b" => problem01_part_2(&input_file),
-        "2a" => problem02_part_1(&input_file),
-        "2b" => problem02_part_2(&input_file),
-        "3a" => problem03_part_1(&input_file),
-        "3b" => problem03_part_2(&input_file),
-        "4a" => problem04_part_1(&input_file),
-        "4b" => problem04_part_2(&input_file),
-        "5a" => problem05_part_1(&input_file),
-        "5b" => problem05_part_2(&input_file),
-        trimmed => {
-            eprintln!("Invalid problem entered - you entered '{}', but a number between {} and {} with either part a or b was expected", trimmed, lower_problem_bound, upper_problem_bound);
-            return ExitCode::from(1);
+// Print a randomly generated input for a day, reproducible from its seed. Without a
+// seed, one is picked from the clock and reported on stderr.
+fn generate_command(rest: &[String], args: &Args) -> ExitCode {
+    let day = match day_argument(rest, "generate", "to generate an input for") {
+        Some(day) => day,
+        None => return Exit::Usage.into(),
+    };
+    let input = match generate_input(day, args.seed, args.size) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return command_exit(&err).into();
         }
     };
-    match result {
-        Ok(_) => return ExitCode::from(0),
+    match io::stdout().write_all(input.as_bytes()) {
+        // e.g. piped into `head`, which is fine
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Exit::Success.into(),
         Err(err) => {
             eprintln!("Error: {}", err);
-            return ExitCode::from(1);
+            return Exit::Io.into();
         }
+        Ok(()) => return Exit::Success.into(),
+    }
+}
+
+// Run every implementation of each part of a day that has variants against its real,
+// example and generated inputs, reporting the first input they disagree on.
+fn crosscheck_command(rest: &[String], args: &Args) -> ExitCode {
+    let day = match day_argument(rest, "crosscheck", "to cross-check") {
+        Some(day) => day,
+        None => return Exit::Usage.into(),
+    };
+    match run_crosschecks(day, &args.input, args.seed, args.size) {
+        Ok(exit) => return exit.into(),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return command_exit(&err).into();
+        }
+    }
+}
+
+// The day given as the only argument to `command`.
+fn day_argument(rest: &[String], command: &str, purpose: &str) -> Option<u32> {
+    match rest {
+        [day] => match day.parse::<u32>() {
+            Ok(day) => return Some(day),
+            Err(_) => {
+                eprintln!("Invalid day '{}' - expected a number, e.g. '{} 5'", day, command);
+                return None;
+            }
+        },
+        [] => {
+            eprintln!("You must provide the day {}, e.g. '{} 5'", purpose, command);
+            return None;
+        }
+        [_, extra, ..] => {
+            eprintln!("Unexpected argument '{}'", extra);
+            return None;
+        }
+    }
+}
+
+// The exit status for an error from a command other than solving. Outside of solving,
+// validation errors are about what was asked for (e.g. a day out of range), so they are
+// reported as usage errors rather than solver failures.
+fn command_exit(err: &Error) -> Exit {
+    match err {
+        Error::Validation(_) => return Exit::Usage,
+        err => return Exit::for_error(err),
+    }
+}
+
+fn lookup_solution(problem: &str) -> Option<&'static dyn Solution> {
+    let solution = find_solution(problem);
+    if solution.is_none() {
+        eprintln!("Invalid problem entered - you entered '{}', but one of the following was expected", problem);
+        eprintln!("{}", usage());
+    }
+    return solution;
+}
+
+// Pair each solution with its input file: the one given on the command line ('-' for
+// stdin), or without one, input piped into stdin, falling back to the solution's
+// conventional input. Every solution reading stdin gets the same input, so it can
+// only be used when every solution is for the same day.
+fn input_jobs(rest: &[String], solutions: &[&'static dyn Solution], args: &Args) -> Option<Vec<(&'static dyn Solution, String)>> {
+    let input_file = match rest {
+        [] if !args.input.example && !args.input.embedded && stdin_is_piped() => Some(String::from(STDIN_PATH)),
+        [] => None,
+        [file] => Some(file.clone()),
+        [_, extra, ..] => {
+            eprintln!("Unexpected argument '{}'", extra);
+            return None;
+        }
+    };
+
+    if input_file.as_deref() == Some(STDIN_PATH) && solutions.iter().any(|solution| solution.day() != solutions[0].day()) {
+        eprintln!("Input from stdin can only be used when solving a single day - give an input file for each day instead");
+        return None;
+    }
+    let jobs = solutions
+        .iter()
+        .map(|solution| match &input_file {
+            Some(input_file) => (*solution, input_file.clone()),
+            None => (*solution, args.input.resolve(solution.day(), solution.part())),
+        })
+        .collect();
+    return Some(jobs);
+}
+
+// Build the usage message, listing every selector the runner accepts.
+fn usage() -> String {
+    let mut help = String::from("Usage: advent-of-code-2023 <problems> [input file] [options]\n");
+    help.push_str("       advent-of-code-2023 all [options]\n");
+    help.push_str("       advent-of-code-2023 bench <problem> [input file] [options]\n");
+    help.push_str("       advent-of-code-2023 verify [answers file] [options]\n");
+    help.push_str("       advent-of-code-2023 new-day <day> [--input-dir <dir>]\n");
+    help.push_str("       advent-of-code-2023 fetch <day>... [--input-dir <dir>]\n");
+    help.push_str("       advent-of-code-2023 guess <problem> <answer> [too high|too low|wrong|correct]\n");
+    help.push_str("       advent-of-code-2023 generate <day> [--seed <n>] [--size <n>]\n");
+    help.push_str("       advent-of-code-2023 crosscheck <day> [--seed <n>] [--size <n>] [--input-dir <dir>]\n\n");
+    help.push_str("Use '-' as the input file to read from stdin. Without an input file, piped input is\n");
+    help.push_str("read from stdin, otherwise <input dir>/NN.txt (or NNexample.txt with --example).\n\n");
+    help.push_str("Options:\n");
+    help.push_str("  --time               report read, parse and solve durations separately\n");
+    help.push_str("  --watch              re-run the problem every time its input file changes\n");
+    help.push_str("  --quiet              don't show a progress line on stderr for slow problems\n");
+    help.push_str("  --timeout <secs>     give up on (and report a timeout for) any problem still solving after this long\n");
+    help.push_str("  --example            use the example input instead of the real input\n");
+    help.push_str("  --input-dir <dir>    directory to look for inputs in (default: problems)\n");
+    help.push_str("  --embedded           use the inputs embedded in the binary (needs the embed-inputs feature)\n");
+    help.push_str("  --format <format>    'text' (default) or 'json' for one JSON object per solved part\n\n");
+    help.push_str("Exit codes: 0 success, 2 usage error, 3 I/O error, 4 parse error, 5 solver error,\n");
+    help.push_str("            6 verification or cross-check mismatch, 7 timeout\n\n");
+    help.push_str("Problems are selected with a selector. ");
+    help.push_str(&selector_help());
+    help.push_str("\n\nAvailable problems (or 'all' to run every problem against its conventional input):");
+    for solution in SOLUTIONS {
+        help.push_str(&format!("\n  {:<4}Day {}: {} (part {})", solution.selector(), solution.day(), solution.title(), solution.part()));
     }
-}
\ No newline at end of file
+    return help;
+}
@@ -0,0 +1,167 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+
+// With the count-allocations feature, every allocation goes through this allocator so
+// that `measure` can report what a piece of code allocated.
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Whether allocations are being counted, i.e. whether `measure` reports anything.
+pub fn is_enabled() -> bool {
+    cfg!(feature = "count-allocations")
+}
+
+// What some code allocated while it ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocationStats {
+    // the number of allocations, counting each reallocation as one more
+    pub allocations: u64,
+    // the total size of every allocation, counting only the growth of reallocations
+    pub bytes_allocated: u64,
+    // the most memory that was live at once, beyond what was live beforehand
+    pub peak_live_bytes: u64,
+    // how much more memory was live afterwards than beforehand (e.g. a parsed model)
+    pub retained_bytes: i64,
+}
+
+impl AllocationStats {
+    // The stats for running this code and then `next`, which starts with whatever
+    // this code retained still live.
+    pub fn then(&self, next: AllocationStats) -> AllocationStats {
+        let next_peak = (self.retained_bytes.max(0) as u64) + next.peak_live_bytes;
+        AllocationStats {
+            allocations: self.allocations + next.allocations,
+            bytes_allocated: self.bytes_allocated + next.bytes_allocated,
+            peak_live_bytes: self.peak_live_bytes.max(next_peak),
+            retained_bytes: self.retained_bytes + next.retained_bytes,
+        }
+    }
+}
+
+impl fmt::Display for AllocationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, peak {}",
+            self.allocations,
+            format_bytes(self.bytes_allocated),
+            format_bytes(self.peak_live_bytes),
+        )
+    }
+}
+
+// A byte count in the largest binary unit that keeps it at least 1, e.g. "3.2 MiB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    return format!("{:.1} {}", value, UNITS[unit]);
+}
+
+// Run `f` and report what it allocated on the current thread, or `None` if
+// allocations aren't being counted. Allocations are counted per thread so that parts
+// running in parallel don't count each other's, which means anything `f` hands off
+// to other threads isn't counted. Measurements can't be nested.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocationStats>) {
+    if !is_enabled() {
+        return (f(), None);
+    }
+
+    let before = COUNTERS.with(|counters| {
+        let mut before = counters.get();
+        before.peak_live_bytes = before.live_bytes;
+        counters.set(before);
+        before
+    });
+    let value = f();
+    let after = COUNTERS.with(Cell::get);
+
+    let stats = AllocationStats {
+        allocations: after.allocations - before.allocations,
+        bytes_allocated: after.bytes_allocated - before.bytes_allocated,
+        peak_live_bytes: (after.peak_live_bytes - before.live_bytes).max(0) as u64,
+        retained_bytes: after.live_bytes - before.live_bytes,
+    };
+    return (value, Some(stats));
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Counters {
+    allocations: u64,
+    bytes_allocated: u64,
+    // signed, as memory allocated on one thread may be freed on another
+    live_bytes: i64,
+    peak_live_bytes: i64,
+}
+
+thread_local! {
+    // a const-initialized `Cell` of plain data never allocates or needs a destructor,
+    // so it's safe to use from inside the allocator
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters { allocations: 0, bytes_allocated: 0, live_bytes: 0, peak_live_bytes: 0 })
+    };
+}
+
+// Record an allocation (or reallocation) that changed the live memory by `size_change`
+// bytes. Allocations made while the thread is shutting down aren't counted.
+fn record(size_change: i64) {
+    let _ = COUNTERS.try_with(|counters| {
+        let mut updated = counters.get();
+        updated.allocations += 1;
+        updated.bytes_allocated += size_change.max(0) as u64;
+        updated.live_bytes += size_change;
+        updated.peak_live_bytes = updated.peak_live_bytes.max(updated.live_bytes);
+        counters.set(updated);
+    });
+}
+
+fn record_free(size: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        let mut updated = counters.get();
+        updated.live_bytes -= size as i64;
+        counters.set(updated);
+    });
+}
+
+// The system allocator, counting what passes through it on each thread.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            record(layout.size() as i64);
+        }
+        return pointer;
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            record(layout.size() as i64);
+        }
+        return pointer;
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            record(new_size as i64 - layout.size() as i64);
+        }
+        return new_pointer;
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        record_free(layout.size());
+    }
+}
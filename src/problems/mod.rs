@@ -1,5 +1,154 @@
+use crate::error::Error;
+use crate::rng::{seed_or_clock, Rng};
+use crate::solution::{Part, Solution};
+
 pub mod problem01;
 pub mod problem02;
 pub mod problem03;
 pub mod problem04;
-pub mod problem05;
\ No newline at end of file
+pub mod problem05;
+
+// Every solution known to the runner, in day/part order. Adding a new day only
+// requires adding its parts here.
+pub static SOLUTIONS: &[&dyn Solution] = &[
+    &problem01::Part1,
+    &problem01::Part2,
+    &problem02::Part1,
+    &problem02::Part2,
+    &problem03::Part1,
+    &problem03::Part2,
+    &problem04::Part1,
+    &problem04::Part2,
+    &problem05::Part1,
+    &problem05::Part2,
+];
+
+// Alternative implementations of registered parts (e.g. an optimized rewrite), which
+// `crosscheck` compares against the main implementation in `SOLUTIONS`. They are only
+// run otherwise when selected by name, e.g. "3a:indexed".
+pub static VARIANTS: &[&dyn Solution] = &[&problem03::Part1Indexed, &problem03::Part2Indexed];
+
+pub fn find_solution(selector: &str) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().chain(VARIANTS).copied().find(|solution| solution.selector() == selector)
+}
+
+// Generate an input for `day` of about `size` records, from `seed` (or the clock, see
+// `seed_or_clock`). Fails if the day doesn't exist or has no generator.
+pub fn generate_input(day: u32, seed: Option<u64>, size: usize) -> Result<String, Error> {
+    let solution = match SOLUTIONS.iter().find(|solution| solution.day() == day) {
+        Some(solution) => solution,
+        None => return Err(Error::Validation(format!("there are no problems for day {}", day))),
+    };
+    if solution.generate(&mut Rng::new(0), 1).is_none() {
+        return Err(Error::Validation(format!("day {} doesn't have an input generator", day)));
+    }
+    match solution.generate(&mut Rng::new(seed_or_clock(seed)), size) {
+        Some(input) => return Ok(input),
+        None => return Err(Error::Internal(format!("day {}'s input generator stopped generating", day))),
+    }
+}
+
+// Every implementation of the given day and part: the main one first, then its variants.
+pub fn implementations(day: u32, part: Part) -> Vec<&'static dyn Solution> {
+    SOLUTIONS
+        .iter()
+        .chain(VARIANTS)
+        .copied()
+        .filter(|solution| solution.day() == day && solution.part() == part)
+        .collect()
+}
+
+// Resolve a selector from the command line into the solutions it names, in day/part
+// order. A selector is a comma-separated list of any of:
+//   3b       a single part
+//   3        both parts of a day
+//   1-5      both parts of every day in a range
+//   a, b     that part of every day
+//   latest   both parts of the most recent day
+pub fn select_solutions(selector: &str) -> Result<Vec<&'static dyn Solution>, String> {
+    let candidates = SOLUTIONS.iter().chain(VARIANTS).copied().collect::<Vec<&'static dyn Solution>>();
+    let mut selected = vec![false; candidates.len()];
+    for term in selector.split(',').map(str::trim) {
+        let term_selection = match parse_selector_term(term) {
+            Some(term_selection) => term_selection,
+            None => return Err(format!("Invalid problem selector '{}'\n{}", term, selector_help())),
+        };
+        let mut found = false;
+        for (index, solution) in candidates.iter().enumerate() {
+            if term_selection.matches(*solution) {
+                selected[index] = true;
+                found = true;
+            }
+        }
+        if !found {
+            return Err(format!("No problems match '{}'\n{}", term, selector_help()));
+        }
+    }
+
+    let mut solutions = candidates.into_iter().zip(selected).filter(|(_, selected)| *selected).map(|(solution, _)| solution).collect::<Vec<_>>();
+    // variants come after all the main solutions in the registry, but are run next to their part
+    solutions.sort_by_key(|solution| (solution.day(), solution.part()));
+    return Ok(solutions);
+}
+
+// The solutions named by a single term of a selector. Only `Single` selects variants.
+enum Selection {
+    // one solution, by its full selector
+    Single(String),
+    // both parts of every day in the inclusive range
+    Days(u32, u32),
+    EveryDay(Part),
+}
+
+impl Selection {
+    fn matches(&self, solution: &dyn Solution) -> bool {
+        match self {
+            Selection::Single(selector) => solution.selector() == *selector,
+            Selection::Days(first, last) => solution.variant().is_none() && (*first..=*last).contains(&solution.day()),
+            Selection::EveryDay(part) => solution.variant().is_none() && solution.part() == *part,
+        }
+    }
+}
+
+fn parse_selector_term(term: &str) -> Option<Selection> {
+    match term {
+        "a" => return Some(Selection::EveryDay(Part::A)),
+        "b" => return Some(Selection::EveryDay(Part::B)),
+        "latest" => {
+            let latest = SOLUTIONS.iter().map(|solution| solution.day()).max()?;
+            return Some(Selection::Days(latest, latest));
+        }
+        _ => {}
+    }
+    if let Some(solution) = find_solution(term) {
+        return Some(Selection::Single(solution.selector()));
+    }
+    if let Ok(day) = term.parse::<u32>() {
+        return Some(Selection::Days(day, day));
+    }
+    let (first, last) = term.split_once('-')?;
+    let (first, last) = (first.trim().parse::<u32>().ok()?, last.trim().parse::<u32>().ok()?);
+    if first > last {
+        return None;
+    }
+    return Some(Selection::Days(first, last));
+}
+
+// A description of the selector syntax, listing every part in the registry.
+pub fn selector_help() -> String {
+    let parts = SOLUTIONS.iter().map(|solution| solution.selector()).collect::<Vec<String>>();
+    let days = SOLUTIONS.iter().map(|solution| solution.day());
+    let (first_day, last_day) = (days.clone().min().unwrap_or(0), days.max().unwrap_or(0));
+
+    let mut help = String::from("Expected a comma-separated list (e.g. '2b,4a') of any of:\n");
+    help.push_str(&format!("  a single part       one of {}\n", parts.join(", ")));
+    if !VARIANTS.is_empty() {
+        let variants = VARIANTS.iter().map(|solution| solution.selector()).collect::<Vec<String>>();
+        help.push_str(&format!("  a variant of a part one of {}\n", variants.join(", ")));
+    }
+    help.push_str(&format!("  a day               both parts, e.g. '{}' (days {} to {})\n", last_day, first_day, last_day));
+    help.push_str(&format!("  a range of days     e.g. '{}-{}'\n", first_day, last_day));
+    help.push_str("  'a' or 'b'          that part of every day\n");
+    help.push_str(&format!("  'latest'            both parts of the most recent day (day {})", last_day));
+    return help;
+}
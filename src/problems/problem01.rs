@@ -1,173 +1,150 @@
-use std::error::Error;
+use crate::error::Error;
+use crate::rng::Rng;
+use crate::solution::{downcast, Parsed, Part, Solution, Solved};
 
-use crate::file::{read_file};
+const DAY: u32 = 1;
+const TITLE: &str = "Trebuchet?!";
 
-pub fn problem01_part_1(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let input = read_file(input_file)?;
-    let lines: Vec<&str> = input.lines().collect();
+pub struct Part1;
 
+impl Solution for Part1 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_calibration_document(input)))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem01_part_1(downcast::<Vec<String>>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_calibration_document(rng, size))
+    }
+}
+
+pub struct Part2;
+
+impl Solution for Part2 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::B }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_calibration_document(input)))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem01_part_2(downcast::<Vec<String>>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_calibration_document(rng, size))
+    }
+}
+
+// The calibration document is just its lines; the digits are found while solving
+// since each part looks for them differently.
+pub fn parse_calibration_document(input: &str) -> Vec<String> {
+    return input.lines().map(String::from).collect();
+}
+
+pub fn problem01_part_1(lines: &[String]) -> Result<Solved, Error> {
     let mut total_sum: u32 = 0;
-    for line in lines {
+    for (line_index, line) in lines.iter().enumerate() {
         let first_digit = if let Some(first_digit) = line.chars().find(|&c| c.is_numeric()) {
             first_digit
         } else {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Improper input file format - all lines must contain at least 2 digits",
-            )));
+            return Err(Error::parse(line_index, line, line.len(), "at least one digit in the line"));
         };
         
         let last_digit = if let Some(last_digit) = line.chars().rev().find(|&c| c.is_numeric()) {
             last_digit
         } else {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Improper input file format - all lines must contain at least 2 digits",
-            )));
+            return Err(Error::parse(line_index, line, line.len(), "at least one digit in the line"));
         };
 
-        let mut combined = first_digit.to_string();
-        combined.push(last_digit);
-        total_sum += combined.parse::<u32>().unwrap();
+        total_sum += calibration_value(line_index, line, first_digit, last_digit)?;
     }
 
-    println!("Sum of calibration values: {}", total_sum);
-
-    return Ok(());
+    return Ok(Solved::new(total_sum).with_label("Sum of calibration values"));
 }
 
-pub fn problem01_part_2(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let input = read_file(input_file)?;
-    let lines: Vec<&str> = input.lines().collect();
-
+pub fn problem01_part_2(lines: &[String]) -> Result<Solved, Error> {
     let mut total_sum: u32 = 0;
-    for line in lines {
-        let first_digit = if let Some(first_digit) = find_first_digit(&line, false) {
+    for (line_index, line) in lines.iter().enumerate() {
+        let first_digit = if let Some(first_digit) = find_first_digit(line, false) {
             first_digit
         } else {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Improper input file format - all lines must contain at least 2 digits",
-            )));
+            return Err(Error::parse(line_index, line, line.len(), "at least one digit or spelled-out digit in the line"));
         };
 
-        let last_digit = if let Some(last_digit) = find_first_digit(&line, true) {
+        let last_digit = if let Some(last_digit) = find_first_digit(line, true) {
             last_digit
         } else {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Improper input file format - all lines must contain at least 2 digits",
-            )));
+            return Err(Error::parse(line_index, line, line.len(), "at least one digit or spelled-out digit in the line"));
         };
 
-        let mut combined = first_digit.to_string();
-        combined.push(last_digit);
-        total_sum += combined.parse::<u32>().unwrap();
+        total_sum += calibration_value(line_index, line, first_digit, last_digit)?;
     }
 
-    println!("Sum of calibration values: {}", total_sum);
+    return Ok(Solved::new(total_sum).with_label("Sum of calibration values"));
+}
 
-    return Ok(());
+// Combine the first and last digit of a line into its two-digit calibration value.
+fn calibration_value(line_index: usize, line: &str, first_digit: char, last_digit: char) -> Result<u32, Error> {
+    let mut combined = first_digit.to_string();
+    combined.push(last_digit);
+    combined.parse::<u32>().map_err(|_| {
+        let column = line.find(first_digit).unwrap_or(0);
+        Error::parse(line_index, line, column, "decimal digits 0-9")
+    })
 }
 
-fn find_first_digit(input: &str, backwards: bool) -> Option<char> {
-    let range: Box<dyn Iterator<Item = usize>> = if backwards {
-        Box::new((0..input.len()).rev())
-    } else {
-        Box::new(0..input.len())
-    };
-    for i in range {
-        match input.chars().nth(i) {
-            Some(character) if character.is_numeric() => {
-                return Some(character);
-            }
-            Some('z') => {
-                if starts_with_0(&input[i..]) {
-                    return Some('0');
-                }
-            }
-            Some('o') => {
-                if starts_with_1(&input[i..]) {
-                    return Some('1');
-                }
-            }
-            Some('t') => {
-                if starts_with_2(&input[i..]) {
-                    return Some('2');
-                } else if starts_with_3(&input[i..]) {
-                    return Some('3');
-                }
-            }
-            Some('f') => {
-                if starts_with_4(&input[i..]) {
-                    return Some('4');
-                } else if starts_with_5(&input[i..]) {
-                    return Some('5');
-                }
-            }
-            Some('s') => {
-                if starts_with_6(&input[i..]) {
-                    return Some('6');
-                } else if starts_with_7(&input[i..]) {
-                    return Some('7');
-                }
-            }
-            Some('e') => {
-                if starts_with_8(&input[i..]) {
-                    return Some('8');
-                }
-            }
-            Some('n') => {
-                if starts_with_9(&input[i..]) {
-                    return Some('9');
+const SPELLED_DIGITS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+// Generate a calibration document of `size` lines, each a jumble of letters, digits
+// and spelled-out digits with at least one digit in it.
+pub fn generate_calibration_document(rng: &mut Rng, size: usize) -> String {
+    let mut document = String::new();
+    for _ in 0..size {
+        let pieces = rng.range(1, 8);
+        let digit_piece = rng.range(0, pieces - 1);
+        for piece in 0..pieces {
+            if piece == digit_piece || rng.chance(0.2) {
+                document.push(char::from(b'0' + rng.range(1, 9) as u8));
+            } else if rng.chance(0.4) {
+                let spelled_digit = rng.choose(&SPELLED_DIGITS);
+                document.push_str(spelled_digit);
+            } else {
+                for _ in 0..rng.range(1, 4) {
+                    document.push(char::from(b'a' + rng.range(0, 25) as u8));
                 }
             }
-            Some(_) => (), // do nothing - proceed with loop
-            None => {
-                return None; // end of string - no digits found
-            }
         }
+        document.push('\n');
     }
 
-    return None;
-}
-
-fn starts_with_0(input: &str) -> bool {
-    return input.chars().count() >= 4 && &input[0..4] == "zero"  
-}
-
-fn starts_with_1(input: &str) -> bool {
-    return input.chars().count() >= 3 && &input[0..3] == "one"
-}
-
-fn starts_with_2(input: &str) -> bool {
-    return input.chars().count() >= 3 && &input[0..3] == "two"
-}
-
-fn starts_with_3(input: &str) -> bool {
-    return input.chars().count() >= 5 && &input[0..5] == "three"
-}
-
-fn starts_with_4(input: &str) -> bool {
-    return input.chars().count() >= 4 && &input[0..4] == "four"
-}
-
-fn starts_with_5(input: &str) -> bool {
-    return input.chars().count() >= 4 && &input[0..4] == "five"
+    return document;
 }
 
-fn starts_with_6(input: &str) -> bool {
-    return input.chars().count() >= 3 && &input[0..3] == "six"
-}
-
-fn starts_with_7(input: &str) -> bool {
-    return input.chars().count() >= 5 && &input[0..5] == "seven"
-}
+// Find the first (or with `backwards`, the last) digit in a line, either as a numeral
+// or spelled out. Positions are byte offsets at character boundaries, so any UTF-8
+// input is safe to search.
+pub fn find_first_digit(input: &str, backwards: bool) -> Option<char> {
+    let positions: Box<dyn Iterator<Item = (usize, char)>> = if backwards {
+        Box::new(input.char_indices().rev())
+    } else {
+        Box::new(input.char_indices())
+    };
+    for (i, character) in positions {
+        if character.is_numeric() {
+            return Some(character);
+        }
+        let rest = &input[i..];
+        if rest.starts_with("zero") {
+            return Some('0');
+        }
+        if let Some(index) = SPELLED_DIGITS.iter().position(|spelled_digit| rest.starts_with(spelled_digit)) {
+            return char::from_digit(index as u32 + 1, 10);
+        }
+    }
 
-fn starts_with_8(input: &str) -> bool {
-    return input.chars().count() >= 5 && &input[0..5] == "eight"
+    return None;
 }
-
-fn starts_with_9(input: &str) -> bool {
-    return input.chars().count() >= 4 && &input[0..4] == "nine"
-}
\ No newline at end of file
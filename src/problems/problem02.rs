@@ -1,174 +1,192 @@
-use std::error::Error;
+use crate::error::Error;
+use crate::rng::Rng;
+use crate::solution::{downcast, Parsed, Part, Solution, Solved};
 
-use crate::file::{read_file};
+pub const RED_CUBES: u32 = 12;
+pub const GREEN_CUBES: u32 = 13;
+pub const BLUE_CUBES: u32 = 14;
 
-const RED_CUBES: u32 = 12;
-const GREEN_CUBES: u32 = 13;
-const BLUE_CUBES: u32 = 14;
+const DAY: u32 = 2;
+const TITLE: &str = "Cube Conundrum";
 
-pub fn problem02_part_1(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let input = read_file(input_file)?;
-    let lines: Vec<&str> = input.lines().collect();
-   
+pub struct Part1;
+
+impl Solution for Part1 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_games(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem02_part_1(downcast::<Vec<Game>>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_games(rng, size))
+    }
+}
+
+pub struct Part2;
+
+impl Solution for Part2 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::B }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_games(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem02_part_2(downcast::<Vec<Game>>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_games(rng, size))
+    }
+}
+
+pub fn problem02_part_1(games: &[Game]) -> Result<Solved, Error> {
     let mut sum_of_ids: u32 = 0;
-    for line in lines {
-        let game_id = match get_game_id(&line) {
-            Some(id) => id,
-            None => {
-                return Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Unable to process game id")
-                )));
-            }
-        };
-        let line_without_id = line.split(": ").collect::<Vec<&str>>()[1];
-        match is_game_possible(&line_without_id, RED_CUBES, GREEN_CUBES, BLUE_CUBES) {
-            Ok(true) => {
-                sum_of_ids += game_id;
-            }
-            Ok(false) => {} // intentional no-op - do not add the game sum if not possible
-            Err(err) => {
-                return Err(err);
-            }
+    for game in games {
+        if is_game_possible(game, RED_CUBES, GREEN_CUBES, BLUE_CUBES) {
+            sum_of_ids += game.id;
         }
+        // otherwise intentional no-op - do not add the game sum if not possible
     }
 
-    println!("Sum of possible game IDs: {}", sum_of_ids);
-
-    return Ok(());
+    return Ok(Solved::new(sum_of_ids).with_label("Sum of possible game IDs"));
 }
 
-pub fn problem02_part_2(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let input = read_file(input_file)?;
-    let lines: Vec<&str> = input.lines().collect();
-   
+pub fn problem02_part_2(games: &[Game]) -> Result<Solved, Error> {
     let mut sum_of_power: u32 = 0;
-    for line in lines {
-        let line_without_id = line.split(": ").collect::<Vec<&str>>()[1];
-        match get_max_cube_values_for_game(&line_without_id) {
-            Ok((red, green, blue)) => {
-                sum_of_power += red * green * blue;
-            }
-            Err(err) => {
-                return Err(err);
+    for game in games {
+        let (red, green, blue) = get_max_cube_values_for_game(game);
+        sum_of_power += red * green * blue;
+    }
+
+    return Ok(Solved::new(sum_of_power).with_label("Sum of game powers"));
+}
+
+pub fn parse_games(input: &str) -> Result<Vec<Game>, Error> {
+    let mut games = Vec::new();
+    for (line_index, line) in input.lines().enumerate() {
+        games.push(parse_game(line_index, line)?);
+    }
+
+    return Ok(games);
+}
+
+// Parse a line of the form "Game N: X blue, Y red; Z green, ..." into a game.
+pub fn parse_game(line_index: usize, line: &str) -> Result<Game, Error> {
+    let id = get_game_id(line_index, line)?;
+    let mut rounds = Vec::new();
+    for round in get_rounds_section(line_index, line)?.split("; ") {
+        rounds.push(get_cubes_in_round(line_index, line, round)?);
+    }
+
+    return Ok(Game { id, rounds });
+}
+
+// Generate `size` games of up to six rounds, each revealing up to 20 cubes of one to
+// three colors in any order.
+pub fn generate_games(rng: &mut Rng, size: usize) -> String {
+    let mut games = String::new();
+    for id in 1..=size {
+        let mut rounds = Vec::new();
+        for _ in 0..rng.range(1, 6) {
+            let mut colors = ["red", "green", "blue"];
+            rng.shuffle(&mut colors);
+            let mut cubes = Vec::new();
+            for color in &colors[..rng.index(3) + 1] {
+                cubes.push(format!("{} {}", rng.range(1, 20), color));
             }
+            rounds.push(cubes.join(", "));
         }
+        games.push_str(&format!("Game {}: {}\n", id, rounds.join("; ")));
     }
 
-    println!("Sum of game powers: {}", sum_of_power);
+    return games;
+}
 
-    return Ok(());
+// Given a line of the form "Game N: ...", return N.
+pub fn get_game_id(line_index: usize, line: &str) -> Result<u32, Error> {
+    let game_id_str = match line.split(": ").next().and_then(|section| section.strip_prefix("Game ")) {
+        Some(id) => id,
+        None => return Err(Error::parse(line_index, line, 0, "a line starting with 'Game <id>: '")),
+    };
+    match game_id_str.parse::<u32>() {
+        Ok(id) => return Ok(id),
+        Err(_) => return Err(Error::parse_at(line_index, line, game_id_str, "a numeric game id")),
+    }
 }
 
-fn get_game_id(game_input: &str) -> Option<u32> {
-    let game_id_str = game_input.split(": ").collect::<Vec<&str>>().get(0)?.strip_prefix("Game ");
-    match game_id_str {
-        Some(id) => {
-            return id.parse::<u32>().ok();
-        }
-        None => {
-            return None;
-        }
+// Given a line of the form "Game N: <rounds>", return the rounds section after the id.
+pub fn get_rounds_section(line_index: usize, line: &str) -> Result<&str, Error> {
+    match line.split_once(": ") {
+        Some((_, rounds)) => return Ok(rounds),
+        None => return Err(Error::parse(line_index, line, line.len(), "': ' separating the game id from its rounds")),
     }
 }
 
-fn is_game_possible(game_input: &str, red_cubes: u32, green_cubes: u32, blue_cubes: u32) -> Result<bool, Box<dyn Error>> {
-    let rounds = game_input.split("; ").collect::<Vec<&str>>();
-    for round in rounds {
-        match get_cubes_in_round(&round) {
-            Ok((red, green, blue)) => {
-                if red > red_cubes || green > green_cubes || blue > blue_cubes {
-                    // if any cubes exceed their expected amounts, the game is not possible
-                    return Ok(false);
-                }
-            }
-            Err(err) => {
-                return Err(err);
-            }
+pub fn is_game_possible(game: &Game, red_cubes: u32, green_cubes: u32, blue_cubes: u32) -> bool {
+    for (red, green, blue) in game.rounds.iter() {
+        if *red > red_cubes || *green > green_cubes || *blue > blue_cubes {
+            // if any cubes exceed their expected amounts, the game is not possible
+            return false;
         }
     }
-    return Ok(true); // if we reach this point then no rounds are impossible, and thus the game is possible
+    return true; // if we reach this point then no rounds are impossible, and thus the game is possible
 }
 
 // Get the maximum value for each of the red, green, and blue cubes for a game.
 // The maximum values for the red, green, and blue cubes are returned in that order.
-fn get_max_cube_values_for_game(game_input: &str) -> Result<(u32, u32, u32), Box<dyn Error>> {
-    let rounds = game_input.split("; ").collect::<Vec<&str>>();
+pub fn get_max_cube_values_for_game(game: &Game) -> (u32, u32, u32) {
     let mut max_red = 0;
     let mut max_green = 0;
     let mut max_blue = 0;
-    for round in rounds {
-        match get_cubes_in_round(&round) {
-            Ok((red, green, blue)) => {
-                if red > max_red {
-                    max_red = red
-                }
-                if green > max_green {
-                    max_green = green
-                }
-                if blue > max_blue {
-                    max_blue = blue
-                }
-            }
-            Err(err) => {
-                return Err(err);
-            }
+    for (red, green, blue) in game.rounds.iter() {
+        if *red > max_red {
+            max_red = *red
+        }
+        if *green > max_green {
+            max_green = *green
+        }
+        if *blue > max_blue {
+            max_blue = *blue
         }
     }
-    return Ok((max_red, max_green, max_blue));
+    return (max_red, max_green, max_blue);
 }
 
 // Given a string of the form "X blue, Y green, Z red" where the red, green, and blue
 // numbers can come in any order and where X, Y, and Z are u32 integers, return the 
-// number of red, green, and blue cubes (in that order).
-fn get_cubes_in_round(round_input: &str) -> Result<(u32, u32, u32), Box<dyn Error>> {
+// number of red, green, and blue cubes (in that order). The line the round came from
+// is used to report the position of any malformed cube counts.
+pub fn get_cubes_in_round(line_index: usize, line: &str, round_input: &str) -> Result<(u32, u32, u32), Error> {
     let mut red: u32 = 0;
     let mut green: u32 = 0;
     let mut blue: u32 = 0;
     for cube_str in round_input.split(", ") {
-        if cube_str.ends_with(" red") {
-            let without_suffix = if let Some(stripped) = cube_str.strip_suffix(" red") {
-                stripped
-            } else {
-                return Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Unexpected error strippping ' red' suffix")
-                )));
-            };
-            red = match without_suffix.parse::<u32>() {
-                Ok(value) => value,
-                Err(err) => return Err(Box::new(err)),
-            }
-        }
-        if cube_str.ends_with(" green") {
-            let without_suffix = if let Some(stripped) = cube_str.strip_suffix(" green") {
-                stripped
-            } else {
-                return Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Unexpected error strippping ' green' suffix")
-                )));
-            };
-            green = match without_suffix.parse::<u32>() {
-                Ok(value) => value,
-                Err(err) => return Err(Box::new(err)),
-            }
-        }
-        if cube_str.ends_with(" blue") {
-            let without_suffix = if let Some(stripped) = cube_str.strip_suffix(" blue") {
-                stripped
-            } else {
-                return Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Unexpected error strippping ' blue' suffix")
-                )));
-            };
-            blue = match without_suffix.parse::<u32>() {
-                Ok(value) => value,
-                Err(err) => return Err(Box::new(err)),
-            }
+        let (count, color) = match cube_str.split_once(' ') {
+            Some(split) => split,
+            None => return Err(Error::parse_at(line_index, line, cube_str, "a cube count followed by a color")),
+        };
+        let count = match count.parse::<u32>() {
+            Ok(value) => value,
+            Err(_) => return Err(Error::parse_at(line_index, line, count, "a numeric cube count")),
+        };
+        match color {
+            "red" => red = count,
+            "green" => green = count,
+            "blue" => blue = count,
+            _ => return Err(Error::parse_at(line_index, line, color, "one of 'red', 'green' or 'blue'")),
         }
     }
 
     return Ok((red, green, blue));
-}
\ No newline at end of file
+}
+
+// A game and the red, green, and blue cubes (in that order) revealed in each round.
+#[derive(Debug)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<(u32, u32, u32)>,
+}
@@ -1,41 +1,107 @@
-use std::error::Error;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 
-use crate::file::{read_file};
+use crate::error::Error;
+use crate::rng::Rng;
+use crate::solution::{downcast, Parsed, Part, Solution, Solved};
 
-const NUM_ADJACENT_PARTS_FOR_GEAR_RATIO: u32 = 2;
+pub const NUM_ADJACENT_PARTS_FOR_GEAR_RATIO: u32 = 2;
 
-pub fn problem03_part_1(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let engine_schematic = match parse_engine_schematic(input_file) {
-        Ok(schematic) => schematic,
-        Err(err) => return Err(err),
-    };
+const DAY: u32 = 3;
+const TITLE: &str = "Gear Ratios";
 
-    let sum_of_adjacent_parts: u32 = find_adjacent_part_numbers(&engine_schematic).iter().sum();
-    println!("Sum of adjacent parts: {}", sum_of_adjacent_parts);
+pub struct Part1;
 
-    return Ok(());
+impl Solution for Part1 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_engine_schematic(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem03_part_1(downcast::<EngineSchematic>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_engine_schematic(rng, size))
+    }
 }
 
-pub fn problem03_part_2(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let engine_schematic = match parse_engine_schematic(input_file) {
-        Ok(schematic) => schematic,
-        Err(err) => return Err(err),
-    };
+pub struct Part2;
+
+impl Solution for Part2 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::B }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_engine_schematic(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem03_part_2(downcast::<EngineSchematic>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_engine_schematic(rng, size))
+    }
+}
+
+// Both parts again, looking up the cells around each part number in an index of
+// symbol positions instead of comparing every part number with every symbol.
+pub struct Part1Indexed;
+
+impl Solution for Part1Indexed {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { TITLE }
+    fn variant(&self) -> Option<&'static str> { Some("indexed") }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_engine_schematic(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem03_part_1_indexed(downcast::<EngineSchematic>(parsed)?)
+    }
+}
+
+pub struct Part2Indexed;
+
+impl Solution for Part2Indexed {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::B }
+    fn title(&self) -> &'static str { TITLE }
+    fn variant(&self) -> Option<&'static str> { Some("indexed") }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_engine_schematic(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem03_part_2_indexed(downcast::<EngineSchematic>(parsed)?)
+    }
+}
+
+pub fn problem03_part_1(engine_schematic: &EngineSchematic) -> Result<Solved, Error> {
+    let sum_of_adjacent_parts: u32 = find_adjacent_part_numbers(engine_schematic).iter().sum();
+    return Ok(Solved::new(sum_of_adjacent_parts).with_label("Sum of adjacent parts"));
+}
+
+pub fn problem03_part_2(engine_schematic: &EngineSchematic) -> Result<Solved, Error> {
+    let sum_of_gear_ratios: u32 = find_gear_ratios(engine_schematic, NUM_ADJACENT_PARTS_FOR_GEAR_RATIO).iter().sum();
+    return Ok(Solved::new(sum_of_gear_ratios).with_label("Sum of gear ratios"));
+}
 
-    let sum_of_gear_ratios: u32 = find_gear_ratios(&engine_schematic, NUM_ADJACENT_PARTS_FOR_GEAR_RATIO).iter().sum();
-    println!("Sum of gear ratios: {}", sum_of_gear_ratios);
+pub fn problem03_part_1_indexed(engine_schematic: &EngineSchematic) -> Result<Solved, Error> {
+    let sum_of_adjacent_parts: u32 = find_adjacent_part_numbers_indexed(engine_schematic).iter().sum();
+    return Ok(Solved::new(sum_of_adjacent_parts).with_label("Sum of adjacent parts"));
+}
 
-    return Ok(());
+pub fn problem03_part_2_indexed(engine_schematic: &EngineSchematic) -> Result<Solved, Error> {
+    let sum_of_gear_ratios: u32 = find_gear_ratios_indexed(engine_schematic, NUM_ADJACENT_PARTS_FOR_GEAR_RATIO).iter().sum();
+    return Ok(Solved::new(sum_of_gear_ratios).with_label("Sum of gear ratios"));
 }
 
-fn parse_engine_schematic(input_file: &str) -> Result<EngineSchematic, Box<dyn Error>> {
+pub fn parse_engine_schematic(input: &str) -> Result<EngineSchematic, Error> {
     let mut engine_schematic = EngineSchematic {
         part_numbers: Vec::new(),
         symbols: Vec::new(),
     };
 
-    let input = read_file(input_file)?;
     let lines = input.lines();
     for (row, line) in lines.enumerate() {
         let mut processing_part_number = false;
@@ -45,10 +111,9 @@ fn parse_engine_schematic(input_file: &str) -> Result<EngineSchematic, Box<dyn E
         for (column, character) in line.char_indices() {
             if character.is_numeric() {
                 // if we've reached a numeric digit, process a part number (either start to or continue to)
-                let parsed_digit = character.to_digit(10).ok_or(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Character on line {} and column {} cannot be parsed to integer", row, column)
-                )))?;
+                let parsed_digit = character
+                    .to_digit(10)
+                    .ok_or_else(|| Error::parse(row, line, column, "a decimal digit 0-9"))?;
 
                 if !processing_part_number {
                     // if we're not already processing a part number, store our starting column
@@ -99,7 +164,47 @@ fn parse_engine_schematic(input_file: &str) -> Result<EngineSchematic, Box<dyn E
     return Ok(engine_schematic);
 }
 
-fn find_adjacent_part_numbers(engine_schematic: &EngineSchematic) -> Vec<u32> {
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '=', '%', '@', '&', '-'];
+
+// Generate a `size` by `size` schematic of part numbers of up to three digits
+// scattered among symbols, with gears ('*') more common than other symbols.
+pub fn generate_engine_schematic(rng: &mut Rng, size: usize) -> String {
+    let mut schematic = String::new();
+    for _ in 0..size {
+        let mut column = 0;
+        while column < size {
+            let digits = rng.range(1, 3) as u32;
+            if column + digits as usize <= size && rng.chance(0.15) {
+                schematic.push_str(&rng.range(10u64.pow(digits - 1), 10u64.pow(digits) - 1).to_string());
+                column += digits as usize;
+                // a non-digit always follows a number, so that numbers don't run together
+                if column < size {
+                    schematic.push(generate_filler(rng));
+                    column += 1;
+                }
+            } else {
+                schematic.push(generate_filler(rng));
+                column += 1;
+            }
+        }
+        schematic.push('\n');
+    }
+
+    return schematic;
+}
+
+// A cell of the schematic that isn't part of a number: mostly '.', sometimes a symbol.
+fn generate_filler(rng: &mut Rng) -> char {
+    if !rng.chance(0.1) {
+        return '.';
+    }
+    if rng.chance(0.4) {
+        return '*';
+    }
+    return *rng.choose(&SYMBOLS);
+}
+
+pub fn find_adjacent_part_numbers(engine_schematic: &EngineSchematic) -> Vec<u32> {
     let mut adjacent_part_numbers = Vec::new();
     for part in engine_schematic.part_numbers.iter() {
         for symbol in engine_schematic.symbols.iter() {
@@ -121,7 +226,7 @@ fn find_adjacent_part_numbers(engine_schematic: &EngineSchematic) -> Vec<u32> {
 
 
 
-fn find_gear_ratios(engine_schematic: &EngineSchematic, num_adjacent_parts: u32) -> Vec<u32> {
+pub fn find_gear_ratios(engine_schematic: &EngineSchematic, num_adjacent_parts: u32) -> Vec<u32> {
     let mut gear_ratios = Vec::new();
 
     for symbol in engine_schematic.symbols.iter() {
@@ -161,26 +266,64 @@ fn find_gear_ratios(engine_schematic: &EngineSchematic, num_adjacent_parts: u32)
     return gear_ratios;
 }
 
+// The same as `find_adjacent_part_numbers`, in time linear in the size of the schematic.
+pub fn find_adjacent_part_numbers_indexed(engine_schematic: &EngineSchematic) -> Vec<u32> {
+    let symbol_positions: HashSet<(u32, u32)> = engine_schematic.symbols.iter().map(|symbol| (symbol.row, symbol.column)).collect();
+    return engine_schematic
+        .part_numbers
+        .iter()
+        .filter(|part| surrounding_cells(part).any(|cell| symbol_positions.contains(&cell)))
+        .map(|part| part.number)
+        .collect();
+}
+
+// The same as `find_gear_ratios`, in time linear in the size of the schematic.
+pub fn find_gear_ratios_indexed(engine_schematic: &EngineSchematic, num_adjacent_parts: u32) -> Vec<u32> {
+    // the part numbers next to each '*', by its (row, column)
+    let mut adjacent_parts: HashMap<(u32, u32), Vec<u32>> = engine_schematic
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.symbol == '*')
+        .map(|symbol| ((symbol.row, symbol.column), Vec::new()))
+        .collect();
+    for part in engine_schematic.part_numbers.iter() {
+        for cell in surrounding_cells(part) {
+            if let Some(parts) = adjacent_parts.get_mut(&cell) {
+                parts.push(part.number);
+            }
+        }
+    }
+
+    return adjacent_parts
+        .values()
+        .filter(|parts| parts.len() == num_adjacent_parts as usize)
+        .map(|parts| parts.iter().product())
+        .collect();
+}
+
+// Every (row, column) within one cell of a part number, including the number's own cells.
+fn surrounding_cells(part: &PartNumber) -> impl Iterator<Item = (u32, u32)> + '_ {
+    let columns = part.start_column.saturating_sub(1)..=part.end_column + 1;
+    return (part.row.saturating_sub(1)..=part.row + 1).flat_map(move |row| columns.clone().map(move |column| (row, column)));
+}
+
 #[derive(Debug)]
-#[allow(unused)]
-struct EngineSchematic {
-    part_numbers: Vec<PartNumber>,
-    symbols: Vec<Symbol>,
+pub struct EngineSchematic {
+    pub part_numbers: Vec<PartNumber>,
+    pub symbols: Vec<Symbol>,
 }
 
 #[derive(Debug)]
-#[allow(unused)]
-struct PartNumber {
-    number: u32,
-    row: u32,
-    start_column: u32,
-    end_column: u32,
+pub struct PartNumber {
+    pub number: u32,
+    pub row: u32,
+    pub start_column: u32,
+    pub end_column: u32,
 }
 
 #[derive(Debug)]
-#[allow(unused)]
-struct Symbol {
-    symbol: char,
-    row: u32,
-    column: u32,
+pub struct Symbol {
+    pub symbol: char,
+    pub row: u32,
+    pub column: u32,
 }
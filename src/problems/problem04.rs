@@ -1,37 +1,74 @@
 use std::collections::HashMap;
-use std::error::Error;
 
-use crate::file::{read_file};
+use crate::cancel;
+use crate::error::Error;
+use crate::progress;
+use crate::rng::Rng;
+use crate::solution::{downcast, Parsed, Part, Solution, Solved};
 
-const VALUE_POWER: u32 = 2;
+pub const VALUE_POWER: u32 = 2;
 
-pub fn problem04_part_1(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let input = read_file(input_file)?;
-    let lines: Vec<&str> = input.lines().collect();
-   
-    let mut sum_of_card_values: u32 = 0;
-    for line in lines {
-        let card = parse_card(&line)?;
+const DAY: u32 = 4;
+const TITLE: &str = "Scratchcards";
+// How many card copies to process between checks for cancellation.
+const COPIES_PER_CHECK: u32 = 1 << 16;
 
-        sum_of_card_values += card.value();
+pub struct Part1;
+
+impl Solution for Part1 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_cards(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem04_part_1(downcast::<Vec<Card>>(parsed)?)
     }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_cards(rng, size))
+    }
+}
 
-    println!("Sum of card values: {}", sum_of_card_values);
+pub struct Part2;
 
-    Ok(())
+impl Solution for Part2 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::B }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_cards(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem04_part_2(downcast::<Vec<Card>>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_cards(rng, size))
+    }
 }
 
-pub fn problem04_part_2(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let input = read_file(input_file)?;
-    let lines: Vec<&str> = input.lines().collect();
+pub fn problem04_part_1(cards: &[Card]) -> Result<Solved, Error> {
+    let mut sum_of_card_values: u32 = 0;
+    for card in cards {
+        sum_of_card_values += card.value();
+    }
+
+    Ok(Solved::new(sum_of_card_values).with_label("Sum of card values"))
+}
 
+pub fn problem04_part_2(cards: &[Card]) -> Result<Solved, Error> {
     let mut num_cards: u32 = 0;
     let mut bonus_cards = HashMap::new();
-    for line in lines {
-        let card = parse_card(&line)?;
+    for (index, card) in cards.iter().enumerate() {
+        cancel::check()?;
+        progress::report(index as u64, cards.len() as u64);
         let times_to_process = 1 + *bonus_cards.get(&card.id).get_or_insert(&0); // 1 for the original card + bonus cards
 
         for _ in 0..times_to_process {
+            // a single card can have millions of copies to process
+            if num_cards.is_multiple_of(COPIES_PER_CHECK) {
+                cancel::check()?;
+            }
             num_cards += 1;
 
             // for each nth win of this card, add a bonus card n further in the stack
@@ -43,60 +80,90 @@ pub fn problem04_part_2(input_file: &str) -> Result<(), Box<dyn Error>> {
         }
     }
 
-    println!("Number of total cards: {}", num_cards);
+    Ok(Solved::new(num_cards).with_label("Number of total cards"))
+}
 
-    Ok(())
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, Error> {
+    let mut cards = Vec::new();
+    for (line_index, line) in input.lines().enumerate() {
+        cards.push(parse_card(line_index, line)?);
+    }
+
+    Ok(cards)
 }
 
-fn parse_card(line: &str) -> Result<Card, Box<dyn Error>> {
-    let split_line = line.split(": ").collect::<Vec<&str>>();
-    let id_section = split_line[0];
-    let winning_numbers_section = split_line[1].split(" | ").collect::<Vec<&str>>()[0];
-    let present_numbers_section = split_line[1].split(" | ").collect::<Vec<&str>>()[1];
+pub fn parse_card(line_index: usize, line: &str) -> Result<Card, Error> {
+    let (id_section, numbers_section) = match line.split_once(": ") {
+        Some(sections) => sections,
+        None => return Err(Error::parse(line_index, line, line.len(), "': ' separating the card id from its numbers")),
+    };
+    let (winning_numbers_section, present_numbers_section) = match numbers_section.split_once(" | ") {
+        Some(sections) => sections,
+        None => return Err(Error::parse(line_index, line, line.len(), "' | ' separating the winning numbers from the present numbers")),
+    };
 
     let id = match id_section.strip_prefix("Card ") {
-        Some(id) => id.trim().parse::<u32>()?,
-        None => return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Could not parse id for card")
-        ))),
+        Some(id) => id.trim().parse::<u32>().map_err(|_| Error::parse_at(line_index, line, id.trim(), "a numeric card id"))?,
+        None => return Err(Error::parse(line_index, line, 0, "a line starting with 'Card <id>: '")),
     };
 
-    let winning_numbers = winning_numbers_section
-        .split(" ")
-        .map(|num| num.trim())
-        .filter(|num| !num.is_empty())
-        .map(|num| num.parse::<u32>())
-        .filter(|num| num.is_ok())
-        .map(|num| num.unwrap())
-        .collect::<Vec<u32>>();
+    let winning_numbers = parse_numbers(line_index, line, winning_numbers_section)?;
+    let present_numbers = parse_numbers(line_index, line, present_numbers_section)?;
 
-    let present_numbers = present_numbers_section
-        .split(" ")
+    Ok(Card {
+        id,
+        winning_numbers,
+        present_numbers,
+    })
+}
+
+// Parse a space-separated list of numbers (which may be padded with extra spaces).
+fn parse_numbers(line_index: usize, line: &str, section: &str) -> Result<Vec<u32>, Error> {
+    section
+        .split(' ')
         .map(|num| num.trim())
         .filter(|num| !num.is_empty())
-        .map(|num| num.parse::<u32>())
-        .filter(|num| num.is_ok())
-        .map(|num| num.unwrap())
-        .collect::<Vec<u32>>();
+        .map(|num| num.parse::<u32>().map_err(|_| Error::parse_at(line_index, line, num, "a number")))
+        .collect()
+}
 
-    Ok(Card {
-        id: id,
-        winning_numbers: winning_numbers,
-        present_numbers: present_numbers,
-    })
+// Generate `size` cards, each with 10 winning numbers and 25 present numbers between 1
+// and 99. Most cards have no matches: if cards matched as often as random numbers
+// would, the copies won in part 2 would grow exponentially with the number of cards.
+pub fn generate_cards(rng: &mut Rng, size: usize) -> String {
+    let id_width = size.to_string().len();
+    let format_numbers = |numbers: &[u64]| numbers.iter().map(|number| format!("{:>2}", number)).collect::<Vec<String>>().join(" ");
+
+    let mut cards = String::new();
+    for id in 1..=size {
+        let mut numbers = (1..=99).collect::<Vec<u64>>();
+        rng.shuffle(&mut numbers);
+        let matches = if rng.chance(0.7) { 0 } else { rng.index(3) + 1 };
+        let (winning_numbers, other_numbers) = numbers.split_at(10);
+        let mut present_numbers = winning_numbers[..matches].to_vec();
+        present_numbers.extend_from_slice(&other_numbers[..25 - matches]);
+        rng.shuffle(&mut present_numbers);
+
+        cards.push_str(&format!(
+            "Card {:>id_width$}: {} | {}\n",
+            id,
+            format_numbers(winning_numbers),
+            format_numbers(&present_numbers)
+        ));
+    }
+
+    return cards;
 }
 
 #[derive(Debug)]
-#[allow(unused)]
-struct Card {
-    id: u32,
-    winning_numbers: Vec<u32>,
-    present_numbers: Vec<u32>,
+pub struct Card {
+    pub id: u32,
+    pub winning_numbers: Vec<u32>,
+    pub present_numbers: Vec<u32>,
 }
 
 impl Card {
-    fn value(&self) -> u32 {
+    pub fn value(&self) -> u32 {
         let num = self.matching_numbers();
         if num > 0 {
             VALUE_POWER.pow(num - 1)
@@ -105,10 +172,10 @@ impl Card {
         }
     }
 
-    fn matching_numbers(&self) -> u32 {
+    pub fn matching_numbers(&self) -> u32 {
         let mut matching_numbers = 0;
         for number in self.present_numbers.iter() {
-            if self.winning_numbers.contains(&number) {
+            if self.winning_numbers.contains(number) {
                 matching_numbers += 1;
             }
         }
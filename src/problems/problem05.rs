@@ -1,101 +1,111 @@
 use std::collections::HashMap;
-use std::error::Error;
 
-use crate::file::{read_file};
+use crate::cancel;
+use crate::error::Error;
+use crate::progress;
+use crate::rng::Rng;
+use crate::solution::{downcast, Parsed, Part, Solution, Solved};
 
-pub fn problem05_part_1(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let almanac = parse_almanac(&input_file, false)?;
+const DAY: u32 = 5;
+const TITLE: &str = "If You Give A Seed A Fertilizer";
+// How many seeds to map between progress reports and checks for cancellation.
+const SEEDS_PER_CHECK: usize = 1 << 16;
 
-    if almanac.seeds.is_empty() {
-        return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Error parsing Almanac - must contain at least 1 seed")
-        )));
+pub struct Part1;
+
+impl Solution for Part1 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_almanac(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem05_part_1(downcast::<Almanac>(parsed)?)
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_almanac(rng, size))
     }
+}
 
-    let mut almanac_memo = AlmanacMemo {
-        seed_to_soil: HashMap::new(),
-        soil_to_fertilizer: HashMap::new(),
-        fertilizer_to_water: HashMap::new(),
-        water_to_light: HashMap::new(),
-        light_to_temperature: HashMap::new(),
-        temperature_to_humidity: HashMap::new(),
-        humidity_to_location: HashMap::new(),
-    };
-    let mut lowest_seed = almanac.seeds[0];
-    let mut lowest_seed_location = get_seed_location(lowest_seed, &almanac, &mut almanac_memo)
-        .ok_or(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Error getting seed location - could not map seed to location")
-        )))?;
-    for seed in &almanac.seeds {
-        let next_seed_location = get_seed_location(*seed, &almanac, &mut almanac_memo)
-            .ok_or(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Error getting seed location - could not map seed to location")
-            )))?;
-        if next_seed_location < lowest_seed_location {
-            lowest_seed = *seed;
-            lowest_seed_location = next_seed_location;
-        }
+pub struct Part2;
+
+impl Solution for Part2 {
+    fn day(&self) -> u32 { DAY }
+    fn part(&self) -> Part { Part::B }
+    fn title(&self) -> &'static str { TITLE }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Box::new(parse_almanac(input)?))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        problem05_part_2(downcast::<Almanac>(parsed)?)
     }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_almanac(rng, size))
+    }
+}
 
-    println!("Lowest seed location is {} for seed {}", lowest_seed_location, lowest_seed);
+pub fn problem05_part_1(almanac: &Almanac) -> Result<Solved, Error> {
+    find_lowest_seed_location(&almanac.seeds, almanac, 0)
+}
 
-    Ok(())
+// Expanding the seed ranges and then mapping every seed are reported as one run of
+// progress, with each seed counted once in each.
+pub fn problem05_part_2(almanac: &Almanac) -> Result<Solved, Error> {
+    let seeds = expand_seed_ranges(&almanac.seeds)?;
+    find_lowest_seed_location(&seeds, almanac, seeds.len() as u64)
 }
 
-pub fn problem05_part_2(input_file: &str) -> Result<(), Box<dyn Error>> {
-    let almanac = parse_almanac(&input_file, true)?;
+// Treat the seeds as (start, length) pairs and expand each pair into every seed in its range.
+pub fn expand_seed_ranges(raw_seeds: &[u64]) -> Result<Vec<u64>, Error> {
+    let total = raw_seeds.chunks_exact(2).map(|pair| pair[1]).sum::<u64>();
+    let mut seeds = Vec::new();
+    for s in 0..(raw_seeds.len()/2) {
+        let start = raw_seeds[s*2];
+        for seed in start..start+raw_seeds[s*2+1] {
+            if seeds.len() % SEEDS_PER_CHECK == 0 {
+                cancel::check()?;
+                progress::report(seeds.len() as u64, total * 2);
+            }
+            seeds.push(seed);
+        }
+    }
+
+    Ok(seeds)
+}
 
-    if almanac.seeds.is_empty() {
-        return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Error parsing Almanac - must contain at least 1 seed")
-        )));
+// `already_processed` is how much progress was made before mapping the seeds, which
+// counts towards the total reported.
+fn find_lowest_seed_location(seeds: &[u64], almanac: &Almanac, already_processed: u64) -> Result<Solved, Error> {
+    if seeds.is_empty() {
+        return Err(Error::Validation(String::from("Almanac must contain at least 1 seed")));
     }
 
-    let mut almanac_memo = AlmanacMemo {
-        seed_to_soil: HashMap::new(),
-        soil_to_fertilizer: HashMap::new(),
-        fertilizer_to_water: HashMap::new(),
-        water_to_light: HashMap::new(),
-        light_to_temperature: HashMap::new(),
-        temperature_to_humidity: HashMap::new(),
-        humidity_to_location: HashMap::new(),
-    };
-    let mut lowest_seed = almanac.seeds[0];
-    let mut lowest_seed_location = get_seed_location(lowest_seed, &almanac, &mut almanac_memo)
-        .ok_or(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Error getting seed location - could not map seed to location")
-        )))?;
-    for seed in &almanac.seeds {
-        let next_seed_location = get_seed_location(*seed, &almanac, &mut almanac_memo)
-            .ok_or(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Error getting seed location - could not map seed to location")
-            )))?;
+    let mut almanac_memo = AlmanacMemo::default();
+    let mut lowest_seed = seeds[0];
+    let mut lowest_seed_location = get_seed_location(lowest_seed, almanac, &mut almanac_memo)
+        .ok_or_else(|| Error::Internal(String::from("could not map seed to location")))?;
+    for (index, seed) in seeds.iter().enumerate() {
+        if index % SEEDS_PER_CHECK == 0 {
+            cancel::check()?;
+            progress::report(already_processed + index as u64, already_processed + seeds.len() as u64);
+        }
+        let next_seed_location = get_seed_location(*seed, almanac, &mut almanac_memo)
+            .ok_or_else(|| Error::Internal(String::from("could not map seed to location")))?;
         if next_seed_location < lowest_seed_location {
             lowest_seed = *seed;
             lowest_seed_location = next_seed_location;
         }
     }
 
-    println!("Lowest seed location is {} for seed {}", lowest_seed_location, lowest_seed);
-
-    Ok(())
+    Ok(Solved::new(lowest_seed_location).with_label(format!("Lowest seed location for seed {}", lowest_seed)))
 }
 
-fn parse_almanac(input_file: &str, seeds_as_pairs: bool) -> Result<Almanac, Box<dyn Error>> {
-    let input = read_file(input_file)?;
+pub fn parse_almanac(input: &str) -> Result<Almanac, Error> {
     let lines: Vec<&str> = input.lines().collect();
 
     if lines.is_empty() {
-        return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Error parsing Almanac - first line must be a list of seeds")
-        )));
+        return Err(Error::parse(0, "", 0, "a first line listing the seeds"));
     }
 
     let mut almanac = Almanac {
@@ -109,26 +119,14 @@ fn parse_almanac(input_file: &str, seeds_as_pairs: bool) -> Result<Almanac, Box<
         humidity_to_location: Vec::new(),
     };
     match lines[0].strip_prefix("seeds: ") {
-        Some(stripped_line) if !seeds_as_pairs => almanac.seeds = parse_integer_element_line(stripped_line)?,
-        Some(stripped_line) => {
-            let raw_seeds = parse_integer_element_line(stripped_line)?;
-            for s in 0..(raw_seeds.len()/2) {
-                let start = raw_seeds[s*2];
-                for seed in start..start+raw_seeds[s*2+1] {
-                    almanac.seeds.push(seed);
-                }
-            }
-        }
+        Some(stripped_line) => almanac.seeds = parse_integer_element_line(0, lines[0], stripped_line)?,
         None => {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Error parsing Almanac - first line must begin with 'seeds: '")
-            )));
+            return Err(Error::parse(0, lines[0], 0, "a first line beginning with 'seeds: '"));
         }
     }
 
     let mut parsing: u8 = 0; // 0=nothing, 1=seed, 2=soil, 3=fertilizer, 4=water, 5=light, 6=temperature, 7=humidity
-    for line in lines[1..].iter() {
+    for (line_index, line) in lines.iter().enumerate().skip(1) {
         let trimmed_line = line.trim();
         match trimmed_line {
             "seed-to-soil map:" => parsing = 1,
@@ -138,16 +136,13 @@ fn parse_almanac(input_file: &str, seeds_as_pairs: bool) -> Result<Almanac, Box<
             "light-to-temperature map:" => parsing = 5,
             "temperature-to-humidity map:" => parsing = 6,
             "humidity-to-location map:" => parsing = 7,
-            l if l.is_empty() => continue, // skip empty lines
+            "" => continue, // skip empty lines
             _ => {
-                let map_entry = parse_integer_tuple(trimmed_line)?;
+                if parsing == 0 {
+                    return Err(Error::parse_at(line_index, line, trimmed_line, "a map header (e.g. 'seed-to-soil map:')"));
+                }
+                let map_entry = parse_integer_tuple(line_index, line, trimmed_line)?;
                 match parsing {
-                    0 => {
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!("Error parsing Almanac - received non-empty line before getting map header")
-                        )));
-                    },
                     1 => almanac.seed_to_soil.push(map_entry),
                     2 => almanac.soil_to_fertilizer.push(map_entry),
                     3 => almanac.fertilizer_to_water.push(map_entry),
@@ -156,10 +151,7 @@ fn parse_almanac(input_file: &str, seeds_as_pairs: bool) -> Result<Almanac, Box<
                     6 => almanac.temperature_to_humidity.push(map_entry),
                     7 => almanac.humidity_to_location.push(map_entry),
                     _ => {
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!("Unexpected error - parsing type outside of range")
-                        )));
+                        return Err(Error::Internal(String::from("parsing type outside of range")));
                     }
                 }
             }
@@ -169,30 +161,71 @@ fn parse_almanac(input_file: &str, seeds_as_pairs: bool) -> Result<Almanac, Box<
     Ok(almanac)
 }
 
-fn parse_integer_element_line(line: &str) -> Result<Vec<u64>, Box<dyn Error>> {
+// Parse a space-separated list of integers. `line` is the full input line that
+// `elements_section` was taken from, used to report the position of malformed integers.
+fn parse_integer_element_line(line_index: usize, line: &str, elements_section: &str) -> Result<Vec<u64>, Error> {
     let mut elements = Vec::new();
-    let split_line = line.split(" ").map(|elem| elem.trim()).collect::<Vec<_>>();
+    let split_line = elements_section.split(' ').map(|elem| elem.trim()).collect::<Vec<_>>();
     for elem in split_line {
-        let parsed_element = elem.parse::<u64>()?;
+        let parsed_element = elem
+            .parse::<u64>()
+            .map_err(|_| Error::parse_at(line_index, line, elem, "a non-negative integer"))?;
         elements.push(parsed_element);
     }
 
     Ok(elements)
 }
 
-fn parse_integer_tuple(line: &str) -> Result<(u64, u64, u64), Box<dyn Error>> {
-    let integer_vec = parse_integer_element_line(&line)?;
+fn parse_integer_tuple(line_index: usize, line: &str, entry: &str) -> Result<(u64, u64, u64), Error> {
+    let integer_vec = parse_integer_element_line(line_index, line, entry)?;
     if integer_vec.len() != 3 {
-        return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Error parsing Almanac - map entry must include exactly 3 integers")
-        )))
+        return Err(Error::parse_at(line_index, line, entry, "a map entry of exactly 3 integers"));
     }
 
     Ok((integer_vec[0], integer_vec[1], integer_vec[2]))
 }
 
-fn get_seed_location(seed: u64, almanac: &Almanac, memo: &mut AlmanacMemo) -> Option<u64> {
+const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+// Generate an almanac with `size` seed ranges (of up to 100 seeds each) and `size`
+// ranges in each map. Values stay within the u32 range like the real inputs, and
+// within each map neither the source nor the destination ranges overlap.
+pub fn generate_almanac(rng: &mut Rng, size: usize) -> String {
+    let max_value = u64::from(u32::MAX);
+    let mut almanac = String::from("seeds:");
+    for _ in 0..size {
+        almanac.push_str(&format!(" {} {}", rng.range(0, max_value - 100), rng.range(1, 100)));
+    }
+    almanac.push('\n');
+
+    // each range is placed within its own slot of the value space, so none overlap
+    let slot_width = (max_value / size.max(1) as u64).max(2);
+    for map_name in MAP_NAMES {
+        let mut destination_slots = (0..size as u64).collect::<Vec<u64>>();
+        rng.shuffle(&mut destination_slots);
+        let mut entries = Vec::new();
+        for (source_slot, destination_slot) in destination_slots.into_iter().enumerate() {
+            let length = rng.range(1, slot_width / 2);
+            let source = source_slot as u64 * slot_width + rng.range(0, slot_width - length);
+            let destination = destination_slot * slot_width + rng.range(0, slot_width - length);
+            entries.push(format!("{} {} {}", destination, source, length));
+        }
+        rng.shuffle(&mut entries);
+        almanac.push_str(&format!("\n{} map:\n{}\n", map_name, entries.join("\n")));
+    }
+
+    return almanac;
+}
+
+pub fn get_seed_location(seed: u64, almanac: &Almanac, memo: &mut AlmanacMemo) -> Option<u64> {
     let mut key = seed;
     let mut new_key;
     
@@ -245,19 +278,20 @@ fn get_almanac_map(key: u64, map: &Vec<(u64, u64, u64)>, memo: &HashMap<u64, u64
 }
 
 #[derive(Debug)]
-#[allow(unused)]
-struct Almanac {
-    seeds: Vec<u64>,
-    seed_to_soil: Vec<(u64, u64, u64)>,
-    soil_to_fertilizer: Vec<(u64, u64, u64)>,
-    fertilizer_to_water: Vec<(u64, u64, u64)>,
-    water_to_light: Vec<(u64, u64, u64)>,
-    light_to_temperature: Vec<(u64, u64, u64)>,
-    temperature_to_humidity: Vec<(u64, u64, u64)>,
-    humidity_to_location: Vec<(u64, u64, u64)>,
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    pub seed_to_soil: Vec<(u64, u64, u64)>,
+    pub soil_to_fertilizer: Vec<(u64, u64, u64)>,
+    pub fertilizer_to_water: Vec<(u64, u64, u64)>,
+    pub water_to_light: Vec<(u64, u64, u64)>,
+    pub light_to_temperature: Vec<(u64, u64, u64)>,
+    pub temperature_to_humidity: Vec<(u64, u64, u64)>,
+    pub humidity_to_location: Vec<(u64, u64, u64)>,
 }
 
-struct AlmanacMemo {
+// Memoized results of each almanac map lookup, keyed by the value being mapped.
+#[derive(Default)]
+pub struct AlmanacMemo {
     seed_to_soil: HashMap<u64, u64>,
     soil_to_fertilizer: HashMap<u64, u64>,
     fertilizer_to_water: HashMap<u64, u64>,
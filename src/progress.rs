@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::cancel::{self, CancellationToken};
+
+// How often the progress line is redrawn, at most.
+pub const RENDER_INTERVAL: Duration = Duration::from_millis(100);
+
+// Where the progress line is written, e.g. stderr. Each call is given the whole text
+// to write, including the escape codes that redraw or clear the line.
+pub type Output = Arc<dyn Fn(&str) + Send + Sync>;
+
+// The progress of the solver running on this thread, if it's being rendered.
+struct Reporter {
+    id: u64,
+    label: String,
+    start: Instant,
+    last_render: Option<Instant>,
+    output: Output,
+}
+
+thread_local! {
+    static REPORTER: RefCell<Option<Reporter>> = const { RefCell::new(None) };
+}
+
+static NEXT_REPORTER_ID: AtomicU64 = AtomicU64::new(1);
+
+// The reporter whose progress is on the line. Parts solved in parallel each have a
+// reporter, but only one draws at a time: the first with something to show, until its
+// part finishes (or is cancelled) and clears the line for the next.
+struct LineOwner {
+    id: u64,
+    output: Output,
+    // the owner's cancellation token, if it can be cancelled
+    token: Option<CancellationToken>,
+}
+
+static LINE_OWNER: Mutex<Option<LineOwner>> = Mutex::new(None);
+
+fn line_owner() -> MutexGuard<'static, Option<LineOwner>> {
+    LINE_OWNER.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Run `f` (a solver), rendering any progress it reports as a line on stderr prefixed
+// with `label`, which is cleared again when `f` returns. Only worth calling when
+// stderr is a terminal.
+pub fn with_progress<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    let output: Output = Arc::new(|text: &str| {
+        let _ = write!(io::stderr().lock(), "{}", text);
+    });
+    return with_progress_on(label, output, f);
+}
+
+// Run `f`, rendering any progress it reports to `output`.
+pub fn with_progress_on<T>(label: &str, output: Output, f: impl FnOnce() -> T) -> T {
+    let id = NEXT_REPORTER_ID.fetch_add(1, Ordering::Relaxed);
+    let reporter = Reporter { id, label: label.to_string(), start: Instant::now(), last_render: None, output };
+    let previous = REPORTER.with(|current| current.replace(Some(reporter)));
+    let value = f();
+    let reporter = REPORTER.with(|current| current.replace(previous));
+
+    let mut owner = line_owner();
+    if reporter.is_some_and(|reporter| owner.as_ref().is_some_and(|owner| owner.id == reporter.id)) {
+        clear(&mut owner);
+    }
+    return value;
+}
+
+// Report that a solver has processed `processed` of `total` items. Solvers with long
+// loops call this as they go (every so many iterations, to keep it cheap); outside of
+// `with_progress`, or once the solver has been cancelled, it does nothing.
+pub fn report(processed: u64, total: u64) {
+    let _ = REPORTER.try_with(|reporter| {
+        let mut reporter = reporter.borrow_mut();
+        let reporter = match reporter.as_mut() {
+            Some(reporter) => reporter,
+            None => return,
+        };
+        let now = Instant::now();
+        // parts that finish quickly never show a progress line at all
+        if now - reporter.last_render.unwrap_or(reporter.start) < RENDER_INTERVAL {
+            return;
+        }
+
+        // drawn while holding the line, so that another reporter can't draw over it,
+        // and a cancelled solver can't draw once whoever cancelled it has cleared it
+        let mut owner = line_owner();
+        if cancel::check().is_err() {
+            return;
+        }
+        match owner.as_ref() {
+            Some(owner) if owner.id != reporter.id => return,
+            Some(_) => {}
+            None => *owner = Some(LineOwner { id: reporter.id, output: Arc::clone(&reporter.output), token: cancel::current() }),
+        }
+        reporter.last_render = Some(now);
+        let line = format_progress(&reporter.label, processed, total, now - reporter.start);
+        (reporter.output)(&format!("\r\x1b[K{}", line));
+    });
+}
+
+// Clear the line if the solver drawing on it has been cancelled, so that a solver left
+// running in the background (see `cancel`) doesn't leave its progress on screen.
+pub fn clear_cancelled() {
+    let mut owner = line_owner();
+    if owner.as_ref().is_some_and(|owner| owner.token.as_ref().is_some_and(CancellationToken::is_cancelled)) {
+        clear(&mut owner);
+    }
+}
+
+fn clear(owner: &mut Option<LineOwner>) {
+    if let Some(owner) = owner.take() {
+        (owner.output)("\r\x1b[K");
+    }
+}
+
+// e.g. "5b  45.2%  904.0M/2.0G  12.3M/s  ETA 1m30s"
+pub fn format_progress(label: &str, processed: u64, total: u64, elapsed: Duration) -> String {
+    let fraction = if total == 0 { 1.0 } else { processed.min(total) as f64 / total as f64 };
+    let rate = processed as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+    let eta = if rate > 0.0 {
+        format_eta(Duration::from_secs_f64((total.saturating_sub(processed) as f64 / rate).min(u32::MAX as f64)))
+    } else {
+        String::from("?")
+    };
+    return format!(
+        "{}  {:5.1}%  {}/{}  {}/s  ETA {}",
+        label,
+        fraction * 100.0,
+        format_count(processed as f64),
+        format_count(total as f64),
+        format_count(rate),
+        eta
+    );
+}
+
+// A count with a metric suffix, e.g. "12.3M".
+fn format_count(count: f64) -> String {
+    const SUFFIXES: [&str; 4] = ["k", "M", "G", "T"];
+    if count < 1000.0 {
+        return format!("{:.0}", count);
+    }
+    let mut value = count / 1000.0;
+    let mut suffix = 0;
+    while value >= 1000.0 && suffix + 1 < SUFFIXES.len() {
+        value /= 1000.0;
+        suffix += 1;
+    }
+    return format!("{:.1}{}", value, SUFFIXES[suffix]);
+}
+
+// e.g. "1h02m", "3m05s" or "12s".
+fn format_eta(eta: Duration) -> String {
+    let seconds = eta.as_secs();
+    if seconds >= 3600 {
+        return format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60);
+    }
+    if seconds >= 60 {
+        return format!("{}m{:02}s", seconds / 60, seconds % 60);
+    }
+    return format!("{}s", seconds);
+}
@@ -0,0 +1,78 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// A small, fast pseudo-random number generator (SplitMix64) for generating puzzle
+// inputs. The same seed always produces the same sequence on every platform, so a
+// generated input can be reproduced from its seed alone. Not suitable for anything
+// needing unpredictability.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut mixed = self.state;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return mixed ^ (mixed >> 31);
+    }
+
+    // A value in `low..=high`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low <= high, "empty range {}..={}", low, high);
+        let span = high - low;
+        if span == u64::MAX {
+            return self.next_u64();
+        }
+        // rejection sampling, so that every value is equally likely
+        let limit = u64::MAX - (u64::MAX % (span + 1));
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return low + value % (span + 1);
+            }
+        }
+    }
+
+    // An index in `0..len`, e.g. for choosing from a slice of that length.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "can't choose from nothing");
+        return self.range(0, len as u64 - 1) as usize;
+    }
+
+    // True with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        // the top 53 bits give a uniform float in [0, 1)
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        return unit < probability;
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        return &items[self.index(items.len())];
+    }
+
+    // Shuffle in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+// The seed to generate inputs from: `seed` if one was given, or otherwise one based on
+// the current time, which is printed on stderr so that the run can be reproduced.
+pub fn seed_or_clock(seed: Option<u64>) -> u64 {
+    match seed {
+        Some(seed) => return seed,
+        None => {
+            let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since_epoch| since_epoch.as_nanos() as u64);
+            eprintln!("Seed: {}", seed);
+            return seed;
+        }
+    }
+}
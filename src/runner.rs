@@ -0,0 +1,389 @@
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cancel::{with_token, CancellationToken};
+use crate::error::Error;
+use crate::file::{display_path, read_input, InputOptions};
+use crate::json::Json;
+use crate::memory::{format_bytes, measure, AllocationStats};
+use crate::problems::SOLUTIONS;
+use crate::progress::{self, with_progress};
+use crate::solution::{Answer, Parsed, Solution, Solved};
+
+// How parts are run, whatever inputs they're run against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    // The longest each part may spend solving (not counting reading and parsing its
+    // input). A part that runs out of time is cancelled and reported as having timed out
+    pub timeout: Option<Duration>,
+    // Whether to show the progress of slow parts as a line on stderr
+    pub progress: bool,
+}
+
+// How long each phase of a run took: reading the input file, parsing it into the
+// day's model, and solving the part from that model.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timings {
+    pub read: Duration,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.read + self.parse + self.solve
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "read {}, parse {}, solve {} (total {})",
+            format_duration(self.read),
+            format_duration(self.parse),
+            format_duration(self.solve),
+            format_duration(self.total()),
+        )
+    }
+}
+
+// The outcome of running a single solution against an input file.
+pub struct RunResult {
+    pub solution: &'static dyn Solution,
+    pub input_file: String,
+    pub answer: Result<Solved, Error>,
+    pub timings: Timings,
+    // What the run allocated, when built with the count-allocations feature
+    pub allocations: Option<AllocationStats>,
+}
+
+impl RunResult {
+    // "ok", "timeout", or "error" for any other failure.
+    pub fn status(&self) -> &'static str {
+        match &self.answer {
+            Ok(_) => "ok",
+            Err(Error::Timeout(_)) | Err(Error::Cancelled) => "timeout",
+            Err(_) => "error",
+        }
+    }
+
+    // A machine-readable form of this result. Durations are in nanoseconds.
+    pub fn to_json(&self) -> Json {
+        let (answer, label) = match &self.answer {
+            Ok(solved) => (answer_to_json(&solved.answer), Json::from(solved.label.clone())),
+            Err(_) => (Json::Null, Json::Null),
+        };
+        let error = match &self.answer {
+            Ok(_) => Json::Null,
+            Err(err) => error_to_json(err),
+        };
+        let timings = Json::object()
+            .with("read_ns", self.timings.read.as_nanos())
+            .with("parse_ns", self.timings.parse.as_nanos())
+            .with("solve_ns", self.timings.solve.as_nanos())
+            .with("total_ns", self.timings.total().as_nanos());
+        let allocations = match &self.allocations {
+            Some(stats) => Json::object()
+                .with("count", stats.allocations)
+                .with("bytes", stats.bytes_allocated)
+                .with("peak_bytes", stats.peak_live_bytes),
+            None => Json::Null,
+        };
+
+        return Json::object()
+            .with("day", self.solution.day())
+            .with("part", self.solution.part().to_string())
+            .with("variant", self.solution.variant().map_or(Json::Null, Json::from))
+            .with("status", self.status())
+            .with("answer", answer)
+            .with("label", label)
+            .with("timings", timings)
+            .with("allocations", allocations)
+            .with("input", display_path(&self.input_file))
+            .with("error", error);
+    }
+}
+
+fn answer_to_json(answer: &Answer) -> Json {
+    match answer {
+        Answer::Integer(value) => Json::from(*value),
+        Answer::BigInteger(value) => Json::from(*value),
+        Answer::Text(value) => Json::from(value.as_str()),
+    }
+}
+
+fn error_to_json(err: &Error) -> Json {
+    let json = Json::object().with("kind", err.kind()).with("message", err.to_string());
+    match err {
+        Error::Parse { file, line, column, snippet, expected } => json
+            .with("file", file.clone())
+            .with("line", *line)
+            .with("column", *column)
+            .with("snippet", snippet.as_str())
+            .with("expected", expected.as_str()),
+        _ => json,
+    }
+}
+
+pub fn run_solution(solution: &'static dyn Solution, input_file: &str, options: &RunOptions) -> RunResult {
+    let mut results = run_day(&[solution], input_file, options);
+    return results.remove(0);
+}
+
+// Run parts of the same day against one input file, reading and parsing it only once.
+// Both parts of a day parse into the same model, so the first part's parse is shared.
+// Each result records the full read and parse time (and allocations), since every
+// part needed them.
+pub fn run_day(solutions: &[&'static dyn Solution], input_file: &str, options: &RunOptions) -> Vec<RunResult> {
+    let mut timings = Timings::default();
+
+    let start = Instant::now();
+    let (input, read_allocations) = measure(|| read_input(input_file));
+    timings.read = start.elapsed();
+
+    let (parsed, parse_allocations) = match input {
+        Ok(input) => {
+            let start = Instant::now();
+            let (parsed, parse_allocations) = measure(|| solutions[0].parse(&input));
+            timings.parse = start.elapsed();
+            // shared with the threads that solve parts when there's a timeout
+            (parsed.map(Arc::new), parse_allocations)
+        }
+        Err(err) => (Err(Error::from(err)), None),
+    };
+    let allocations = read_allocations.map(|read| read.then(parse_allocations.unwrap_or_default()));
+
+    let mut results = Vec::new();
+    for solution in solutions.iter().copied() {
+        let mut timings = timings;
+        let mut allocations = allocations;
+        let answer = match &parsed {
+            Ok(parsed) => {
+                let start = Instant::now();
+                let (answer, solve_allocations) = match options.timeout {
+                    Some(timeout) => solve_with_timeout(solution, parsed, timeout, options.progress),
+                    None => solve_with_progress(solution, parsed, options.progress),
+                };
+                timings.solve = start.elapsed();
+                allocations = allocations.zip(solve_allocations).map(|(before, solve)| before.then(solve));
+                answer
+            }
+            // each part reports the failure to read or parse the shared input
+            Err(err) => Err(err.clone()),
+        };
+        results.push(RunResult {
+            solution,
+            input_file: input_file.to_string(),
+            answer: answer.map_err(|err| err.in_file(input_file)),
+            timings,
+            allocations,
+        });
+    }
+
+    return results;
+}
+
+fn solve_with_progress(solution: &'static dyn Solution, parsed: &Parsed, show_progress: bool) -> (Result<Solved, Error>, Option<AllocationStats>) {
+    if !show_progress {
+        return measure(|| solution.solve(parsed));
+    }
+    return with_progress(&solution.selector(), || measure(|| solution.solve(parsed)));
+}
+
+// Solve on a thread of its own, cancelling the solver and reporting a timeout if it
+// doesn't finish in time. A solver that never checks for cancellation keeps running
+// in the background until the process exits, but its answer is ignored (and its
+// progress is no longer shown).
+fn solve_with_timeout(
+    solution: &'static dyn Solution,
+    parsed: &Arc<Parsed>,
+    timeout: Duration,
+    show_progress: bool,
+) -> (Result<Solved, Error>, Option<AllocationStats>) {
+    let token = CancellationToken::new();
+    let (sender, receiver) = mpsc::channel();
+    let (parsed, solver_token) = (Arc::clone(parsed), token.clone());
+    let spawned = thread::Builder::new().name(solution.selector()).spawn(move || {
+        let solved = with_token(solver_token, || solve_with_progress(solution, &parsed, show_progress));
+        // nobody is listening any more if the solver timed out
+        let _ = sender.send(solved);
+    });
+    if let Err(err) = spawned {
+        return (Err(Error::from(err)), None);
+    }
+
+    match receiver.recv_timeout(timeout) {
+        Ok(solved) => return solved,
+        Err(RecvTimeoutError::Timeout) => {
+            token.cancel();
+            progress::clear_cancelled();
+            return (Err(Error::Timeout(timeout)), None);
+        }
+        Err(RecvTimeoutError::Disconnected) => return (Err(Error::Internal(String::from("the solver panicked"))), None),
+    }
+}
+
+// Run every registered solution against its conventional input, in day/part order.
+pub fn run_all(input_options: &InputOptions, options: &RunOptions) -> Vec<RunResult> {
+    let jobs = SOLUTIONS
+        .iter()
+        .map(|solution| (*solution, input_options.resolve(solution.day(), solution.part())))
+        .collect::<Vec<_>>();
+    return run_parallel(&jobs, options);
+}
+
+// Run each solution against its input file on a pool of worker threads, one per
+// available core. Parts of the same day sharing an input file are run together so the
+// input is only parsed once. Results are returned in the same order as `jobs`,
+// regardless of which finishes first, so a slow solver only delays its own result.
+pub fn run_parallel(jobs: &[(&'static dyn Solution, String)], options: &RunOptions) -> Vec<RunResult> {
+    // the indices into `jobs` of each group of parts to run together
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (index, (solution, input_file)) in jobs.iter().enumerate() {
+        let group = groups.iter_mut().find(|group| {
+            let (first, first_input) = &jobs[group[0]];
+            // a variant may parse differently, so it only shares a parse with the same variant
+            first.day() == solution.day() && first.variant() == solution.variant() && first_input == input_file
+        });
+        match group {
+            Some(group) => group.push(index),
+            None => groups.push(vec![index]),
+        }
+    }
+
+    let workers = thread::available_parallelism().map_or(1, |count| count.get()).min(groups.len());
+    let next_group = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (groups, next_group) = (&groups, &next_group);
+            scope.spawn(move || loop {
+                let group = match groups.get(next_group.fetch_add(1, Ordering::Relaxed)) {
+                    Some(group) => group,
+                    None => return,
+                };
+                let solutions = group.iter().map(|index| jobs[*index].0).collect::<Vec<_>>();
+                let results = run_day(&solutions, &jobs[group[0]].1, options);
+                for (index, result) in group.iter().copied().zip(results) {
+                    if sender.send((index, result)).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut slots = jobs.iter().map(|_| None).collect::<Vec<Option<RunResult>>>();
+    for (index, result) in receiver {
+        slots[index] = Some(result);
+    }
+    return slots.into_iter().flatten().collect();
+}
+
+// Print the outcome of a single run: the labelled answer on stdout, or the error on stderr.
+// Optionally follow it with how long each phase of the run took.
+pub fn print_result(result: &RunResult, show_timings: bool) {
+    match &result.answer {
+        Ok(solved) => println!("{}", solved),
+        Err(err) => eprintln!("Error: {}", err),
+    }
+    if show_timings {
+        println!("Timings: {}", result.timings);
+        if let Some(allocations) = &result.allocations {
+            println!("Memory: {}", allocations);
+        }
+    }
+}
+
+// Print each result as a single line of JSON.
+pub fn print_json_lines(results: &[RunResult]) {
+    for result in results {
+        println!("{}", result.to_json());
+    }
+}
+
+// Print a summary table of every result, followed by the details of any errors.
+// With `show_timings`, the elapsed time is broken down into read, parse and solve.
+pub fn print_summary_table(results: &[RunResult], show_timings: bool) {
+    let answer_width = results
+        .iter()
+        .map(|result| result.answer.as_ref().map_or(1, |solved| solved.answer.to_string().len()))
+        .max()
+        .unwrap_or(0)
+        .max("Answer".len());
+    // the part, plus the name of any variant, e.g. "b:indexed"
+    let part_label = |result: &RunResult| match result.solution.variant() {
+        Some(variant) => format!("{}:{}", result.solution.part(), variant),
+        None => result.solution.part().to_string(),
+    };
+    let part_width = results.iter().map(|result| part_label(result).len()).max().unwrap_or(0).max("Part".len());
+
+    let mut header = format!("{:>3}  {:<part_width$}  {:<answer_width$}  {:<7}  {:>10}", "Day", "Part", "Answer", "Status", "Elapsed");
+    if show_timings {
+        header.push_str(&format!("  {:>10}  {:>10}  {:>10}", "Read", "Parse", "Solve"));
+    }
+    // allocations are shown with the timings, when they're being counted
+    let show_allocations = show_timings && results.iter().any(|result| result.allocations.is_some());
+    if show_allocations {
+        header.push_str(&format!("  {:>10}  {:>10}  {:>10}", "Allocs", "Allocated", "Peak"));
+    }
+    println!("{}", header);
+
+    for result in results {
+        let answer = match &result.answer {
+            Ok(solved) => solved.answer.to_string(),
+            Err(_) => String::from("-"),
+        };
+        let mut row = format!(
+            "{:>3}  {:<part_width$}  {:<answer_width$}  {:<7}  {:>10}",
+            result.solution.day(),
+            part_label(result),
+            answer,
+            result.status(),
+            format_duration(result.timings.total()),
+        );
+        if show_timings {
+            row.push_str(&format!(
+                "  {:>10}  {:>10}  {:>10}",
+                format_duration(result.timings.read),
+                format_duration(result.timings.parse),
+                format_duration(result.timings.solve),
+            ));
+        }
+        if show_allocations {
+            let allocations = result.allocations.unwrap_or_default();
+            row.push_str(&format!(
+                "  {:>10}  {:>10}  {:>10}",
+                allocations.allocations,
+                format_bytes(allocations.bytes_allocated),
+                format_bytes(allocations.peak_live_bytes),
+            ));
+        }
+        println!("{}", row);
+    }
+
+    for result in results {
+        if let Err(err) = &result.answer {
+            eprintln!("Error in {}: {}", result.solution.selector(), err);
+        }
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.3}ms", duration.as_secs_f64() * 1_000.0)
+    } else {
+        format!("{:.3}s", duration.as_secs_f64())
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+
+const TEMPLATE: &str = include_str!("../templates/problem.rs.template");
+const PROBLEMS_MOD: &str = "src/problems/mod.rs";
+
+// Set up a new day: create `src/problems/problemNN.rs` from the template, register it
+// in `src/problems/mod.rs` (both the module and its parts in `SOLUTIONS`), and create
+// empty `NN.txt` and `NNexample.txt` inputs in `input_dir`. Paths are relative to
+// `root`, which must be the repository root. Returns every file created or changed.
+pub fn new_day(root: &Path, input_dir: &Path, day: u32) -> Result<Vec<PathBuf>, Error> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::Validation(format!("day must be between 1 and 25, got {}", day)));
+    }
+
+    let mod_path = root.join(PROBLEMS_MOD);
+    let mod_contents = fs::read_to_string(&mod_path).map_err(|err| {
+        Error::Validation(format!("could not read {} ({}) - run new-day from the repository root", mod_path.display(), err))
+    })?;
+    let module_path = root.join(format!("src/problems/problem{:02}.rs", day));
+    if module_path.exists() {
+        return Err(Error::Validation(format!("{} already exists", module_path.display())));
+    }
+
+    let example_path = input_dir.join(format!("{:02}example.txt", day));
+    let module = TEMPLATE
+        .replace("{{EXAMPLE_PATH}}", &include_path(&example_path))
+        .replace("{{DAY2}}", &format!("{:02}", day))
+        .replace("{{DAY}}", &day.to_string());
+    let mod_contents = register_day(&mod_contents, day)?;
+
+    let mut changed = Vec::new();
+    fs::create_dir_all(input_dir)?;
+    for input_file in [format!("{:02}.txt", day), format!("{:02}example.txt", day)] {
+        let input_path = input_dir.join(input_file);
+        if !input_path.exists() {
+            fs::write(&input_path, "")?;
+            changed.push(input_path);
+        }
+    }
+    fs::write(&module_path, module)?;
+    changed.push(module_path);
+    fs::write(&mod_path, mod_contents)?;
+    changed.push(mod_path);
+
+    return Ok(changed);
+}
+
+// Add `pub mod problemNN;` and both of the day's parts to the contents of
+// `problems/mod.rs`, keeping the modules and the registry in day order.
+pub fn register_day(mod_contents: &str, day: u32) -> Result<String, Error> {
+    let module_name = format!("problem{:02}", day);
+    let mod_line = format!("pub mod {};", module_name);
+    if mod_contents.lines().any(|line| line.trim() == mod_line) {
+        return Err(Error::Validation(format!("{} is already registered", module_name)));
+    }
+
+    let mut lines = mod_contents.lines().map(String::from).collect::<Vec<String>>();
+
+    // the module declaration goes after the last module for an earlier day
+    let mod_index = insertion_index(&lines, &module_name, |line| line.strip_prefix("pub mod problem"))
+        .ok_or_else(|| Error::Internal(format!("could not find module declarations in {}", PROBLEMS_MOD)))?;
+    lines.insert(mod_index, mod_line);
+
+    // and its parts go after the last registered part for an earlier day, looking only
+    // within `SOLUTIONS` since other registries (such as `VARIANTS`) list parts too
+    let registry_error = || Error::Internal(format!("could not find the SOLUTIONS registry in {}", PROBLEMS_MOD));
+    let registry_start = lines.iter().position(|line| line.starts_with("pub static SOLUTIONS")).ok_or_else(registry_error)?;
+    let registry_end = registry_start + lines[registry_start..].iter().position(|line| line.trim() == "];").ok_or_else(registry_error)?;
+    let registry_index = registry_start
+        + insertion_index(&lines[registry_start..registry_end], &module_name, |line| line.trim().strip_prefix("&problem")).ok_or_else(registry_error)?;
+    lines.insert(registry_index, format!("    &{}::Part2,", module_name));
+    lines.insert(registry_index, format!("    &{}::Part1,", module_name));
+
+    let mut contents = lines.join("\n");
+    if mod_contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    return Ok(contents);
+}
+
+// The path to use in an `include_str!` in `src/problems/` for `path`, which is
+// relative to the repository root unless it's absolute.
+fn include_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if Path::new(&path).is_absolute() {
+        return path;
+    }
+    return format!("../../{}", path.trim_start_matches("./"));
+}
+
+// Find where a line for `module_name` belongs among the lines matched by `strip`, which
+// returns the remainder of a matching line starting with its two-digit day.
+fn insertion_index(lines: &[String], module_name: &str, strip: impl Fn(&str) -> Option<&str>) -> Option<usize> {
+    let new_day = &module_name["problem".len()..];
+    let mut last_match = None;
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(rest) = strip(line) {
+            last_match = Some(index);
+            if rest.get(..2).is_some_and(|existing_day| existing_day > new_day) {
+                return Some(index);
+            }
+        }
+    }
+    return last_match.map(|index| index + 1);
+}
@@ -0,0 +1,172 @@
+use std::any::Any;
+use std::fmt;
+
+use crate::error::Error;
+use crate::rng::Rng;
+
+// Which half of a day's puzzle a solution answers. Selectors on the command line
+// refer to these as 'a' and 'b' (e.g. "3b").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::A => f.pad("a"),
+            Part::B => f.pad("b"),
+        }
+    }
+}
+
+// The value computed by a solution. Most puzzles produce an integer; answers too
+// large for an i64 are kept as big integers, and anything else is free-form text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::BigInteger(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Integer(i64::from(value))
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Integer(value)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Integer(value),
+            Err(_) => Answer::BigInteger(i128::from(value)),
+        }
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Integer(value),
+            Err(_) => Answer::BigInteger(value),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+// An answer along with an optional human readable description of it, e.g.
+// "Sum of gear ratios". The label is only used for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solved {
+    pub answer: Answer,
+    pub label: Option<String>,
+}
+
+impl Solved {
+    pub fn new(answer: impl Into<Answer>) -> Self {
+        Solved {
+            answer: answer.into(),
+            label: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl fmt::Display for Solved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{}: {}", label, self.answer),
+            None => write!(f, "{}", self.answer),
+        }
+    }
+}
+
+// The parsed form of a puzzle input. Each day parses into its own model (e.g. an
+// `Almanac`), which is handed back to the same day's solve step.
+pub type Parsed = Box<dyn Any + Send + Sync>;
+
+// A single solvable part of a day's puzzle. Every problem module exposes one
+// implementation per part, and each implementation is listed in the registry in
+// `problems/mod.rs` so that the runner can find it.
+//
+// Solving happens in two steps so that they can be timed separately: `parse` turns
+// the raw input into the day's model, and `solve` computes the answer from it. Both
+// parts of a day must parse into the same model, since when they are run against the
+// same input it is only parsed once and the model is shared between them.
+pub trait Solution: Sync {
+    fn day(&self) -> u32;
+    fn part(&self) -> Part;
+    fn title(&self) -> &'static str;
+    // Parse the contents of an input file into this day's model.
+    fn parse(&self, input: &str) -> Result<Parsed, Error>;
+    // Solve this part from the model produced by `parse`, returning the answer.
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error>;
+
+    // Parse and solve the contents of an input file in one go.
+    fn solve_input(&self, input: &str) -> Result<Solved, Error> {
+        let parsed = self.parse(input)?;
+        self.solve(&parsed)
+    }
+
+    // Generate a random but well-formed input for this day, for stress testing. `size`
+    // is roughly how many records (lines, games, cards or map ranges) to generate, or
+    // the width and height of a grid. Days without a generator return `None`.
+    fn generate(&self, _rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+
+    // The name of an alternative implementation of a part, e.g. "indexed", or `None`
+    // for the part's main implementation. Variants are only run when asked for by
+    // name, or by `crosscheck` to compare them against the main implementation.
+    fn variant(&self) -> Option<&'static str> {
+        None
+    }
+
+    // The command line selector for this solution, e.g. "3b", or "3b:indexed" for a variant.
+    fn selector(&self) -> String {
+        match self.variant() {
+            Some(variant) => format!("{}{}:{}", self.day(), self.part(), variant),
+            None => format!("{}{}", self.day(), self.part()),
+        }
+    }
+}
+
+// Recover a day's model from its parsed input. This only fails if a solution is
+// handed input that was parsed by a different day.
+pub fn downcast<T: Any>(parsed: &Parsed) -> Result<&T, Error> {
+    parsed
+        .downcast_ref::<T>()
+        .ok_or_else(|| Error::Internal(String::from("parsed input does not match the solution's model")))
+}
@@ -0,0 +1,98 @@
+use crate::answers::Answers;
+use crate::file::InputOptions;
+use crate::problems::SOLUTIONS;
+use crate::runner::{run_parallel, RunOptions, RunResult};
+use crate::solution::Solution;
+
+// The result of checking one solution against its recorded answer.
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: Option<String> },
+    Missing,
+}
+
+pub struct Verification {
+    pub solution: &'static dyn Solution,
+    pub verdict: Verdict,
+    // Only present if the solution was actually run (i.e. its answer is recorded)
+    pub result: Option<RunResult>,
+}
+
+// Run every solution with a recorded answer against its input and compare. Solutions
+// without a recorded answer are reported as missing without being run. The recorded
+// solutions are run in parallel, but verifications are returned in day/part order.
+pub fn verify_all(answers: &Answers, input_options: &InputOptions, options: &RunOptions) -> Vec<Verification> {
+    let jobs = SOLUTIONS
+        .iter()
+        .copied()
+        .filter(|solution| answers.get(&solution.selector()).is_some())
+        .map(|solution| (solution, input_options.resolve(solution.day(), solution.part())))
+        .collect::<Vec<_>>();
+    let mut results = run_parallel(&jobs, options).into_iter();
+
+    let mut verifications = Vec::new();
+    for solution in SOLUTIONS.iter().copied() {
+        let expected = match answers.get(&solution.selector()) {
+            Some(expected) => expected.to_string(),
+            None => {
+                verifications.push(Verification { solution, verdict: Verdict::Missing, result: None });
+                continue;
+            }
+        };
+
+        let result = match results.next() {
+            Some(result) => result,
+            None => break,
+        };
+        let actual = result.answer.as_ref().ok().map(|solved| solved.answer.to_string());
+        let verdict = if actual.as_deref() == Some(expected.as_str()) {
+            Verdict::Pass
+        } else {
+            Verdict::Fail { expected, actual }
+        };
+        verifications.push(Verification { solution, verdict, result: Some(result) });
+    }
+
+    return verifications;
+}
+
+// Print one line per verification, with a diff under any mismatched answer.
+pub fn print_verifications(verifications: &[Verification]) {
+    for verification in verifications {
+        let selector = verification.solution.selector();
+        match &verification.verdict {
+            Verdict::Pass => println!("PASS     {}", selector),
+            Verdict::Missing => println!("MISSING  {}", selector),
+            Verdict::Fail { expected, actual: Some(actual) } => {
+                println!("FAIL     {}", selector);
+                println!("           expected {}", expected);
+                println!("           actual   {}", actual);
+                println!("                    {}^", " ".repeat(first_difference(expected, actual)));
+            }
+            Verdict::Fail { expected, actual: None } => {
+                println!("FAIL     {}", selector);
+                println!("           expected {}", expected);
+                if let Some(Err(err)) = verification.result.as_ref().map(|result| &result.answer) {
+                    println!("           error    {}", err.to_string().replace('\n', "\n                    "));
+                }
+            }
+        }
+    }
+
+    let count = |wanted: fn(&Verdict) -> bool| verifications.iter().filter(|v| wanted(&v.verdict)).count();
+    println!(
+        "\n{} passed, {} failed, {} missing",
+        count(|verdict| matches!(verdict, Verdict::Pass)),
+        count(|verdict| matches!(verdict, Verdict::Fail { .. })),
+        count(|verdict| matches!(verdict, Verdict::Missing)),
+    );
+}
+
+// The character index at which two strings first differ.
+fn first_difference(expected: &str, actual: &str) -> usize {
+    expected
+        .chars()
+        .zip(actual.chars())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.chars().count().min(actual.chars().count()))
+}
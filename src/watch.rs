@@ -0,0 +1,76 @@
+use std::fs;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::error::Error;
+use crate::file::{display_path, EMBEDDED_PREFIX, STDIN_PATH};
+use crate::runner::{print_result, print_summary_table, run_parallel, RunOptions};
+use crate::solution::Solution;
+
+// How often the input file is checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// What is known about the input file the last time it was checked. A missing file is
+// recorded as `None`, so that the solution is re-run once the file reappears (editors
+// often replace a file rather than writing to it in place).
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+fn snapshot(input_file: &str) -> Snapshot {
+    match fs::metadata(input_file) {
+        Ok(metadata) => Snapshot { modified: metadata.modified().ok(), len: metadata.len() },
+        Err(_) => Snapshot { modified: None, len: 0 },
+    }
+}
+
+// Run each solution against its input file, then poll the files' modification times
+// and run them all again every time any of them changes, clearing the screen before
+// each run. Every run shows how long it took: a single part with its timings, and
+// several parts in a table (broken down into read, parse and solve with
+// `show_timings`). Only returns if an input can't be watched (stdin or an embedded
+// input), or if writing to the terminal fails.
+pub fn watch(jobs: &[(&'static dyn Solution, String)], show_timings: bool, options: &RunOptions) -> Result<(), Error> {
+    let mut input_files: Vec<&str> = Vec::new();
+    for (_, input_file) in jobs {
+        if input_file == STDIN_PATH {
+            return Err(Error::Validation(String::from("stdin can't be watched for changes - give an input file instead")));
+        }
+        if input_file.starts_with(EMBEDDED_PREFIX) {
+            return Err(Error::Validation(String::from("embedded inputs never change - watch an input file instead")));
+        }
+        if !input_files.contains(&input_file.as_str()) {
+            input_files.push(input_file);
+        }
+    }
+
+    let mut last_seen = input_files.iter().map(|input_file| snapshot(input_file)).collect::<Vec<Snapshot>>();
+    loop {
+        // clear the screen and move the cursor to the top left
+        print!("\x1b[2J\x1b[H");
+        println!("Watching {} - press Ctrl-C to stop\n", input_files.iter().map(|file| display_path(file)).collect::<Vec<&str>>().join(", "));
+        let results = run_parallel(jobs, options);
+        match results.as_slice() {
+            [result] => {
+                let solution = result.solution;
+                println!("{} (Day {}: {}, part {})", solution.selector(), solution.day(), solution.title(), solution.part());
+                // the point of re-running is to see the effect of a change, timing included
+                print_result(result, true);
+            }
+            _ => print_summary_table(&results, show_timings),
+        }
+        io::stdout().flush()?;
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = input_files.iter().map(|input_file| snapshot(input_file)).collect::<Vec<Snapshot>>();
+            if current != last_seen {
+                last_seen = current;
+                break;
+            }
+        }
+    }
+}
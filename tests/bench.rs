@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use advent_of_code_2023::bench::{bench_solution, BenchConfig, Stats};
+use advent_of_code_2023::problems::find_solution;
+
+fn millis(samples: &[u64]) -> Vec<Duration> {
+    samples.iter().map(|sample| Duration::from_millis(*sample)).collect()
+}
+
+#[test]
+fn summarizes_samples() {
+    let mut samples = millis(&[9, 1, 5, 3, 7, 2, 8, 4, 6, 10]);
+    let stats = Stats::from_samples(&mut samples, 4);
+    assert_eq!(stats.iterations, 10);
+    assert_eq!(stats.warmup_iterations, 4);
+    assert_eq!(stats.min, Duration::from_millis(1));
+    // the mean of the middle two samples, for an even count
+    assert_eq!(stats.median, Duration::from_micros(5500));
+    assert_eq!(stats.mean, Duration::from_micros(5500));
+    // nearest rank: the 10th of 10 samples
+    assert_eq!(stats.p95, Duration::from_millis(10));
+    // population standard deviation of 1..=10ms
+    assert_eq!(stats.std_dev.as_micros(), 2872);
+}
+
+#[test]
+fn summarizes_a_single_sample() {
+    let stats = Stats::from_samples(&mut millis(&[3]), 1);
+    assert_eq!((stats.min, stats.median, stats.mean, stats.p95), (Duration::from_millis(3), Duration::from_millis(3), Duration::from_millis(3), Duration::from_millis(3)));
+    assert_eq!(stats.std_dev, Duration::ZERO);
+}
+
+#[test]
+fn reports_every_statistic() {
+    let stats = Stats::from_samples(&mut millis(&[1, 2, 3]), 2);
+    let report = stats.to_string();
+    assert!(report.starts_with("3 iterations after 2 warm-up runs\n"), "{}", report);
+    for (name, value) in [("min", "1.000ms"), ("median", "2.000ms"), ("mean", "2.000ms"), ("p95", "3.000ms"), ("stddev", "816µs")] {
+        assert!(report.lines().any(|line| line.split_whitespace().eq([name, value])), "no '{} {}' in:\n{}", name, value, report);
+    }
+}
+
+#[test]
+fn iteration_count_stays_within_configured_bounds() {
+    let config = BenchConfig {
+        warmup_time: Duration::ZERO,
+        measurement_time: Duration::from_secs(3600),
+        min_iterations: 2,
+        max_iterations: 7,
+    };
+    let stats = bench_solution(find_solution("1a").unwrap(), "1abc2\n", &config).unwrap();
+    assert_eq!(stats.iterations, 7);
+    assert_eq!(stats.warmup_iterations, 1);
+}
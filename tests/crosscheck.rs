@@ -0,0 +1,93 @@
+use advent_of_code_2023::crosscheck::{crosscheck, crosscheck_day, crosscheck_inputs, has_variants, minimize_input, CrosscheckInput, Outcome, GENERATED_INPUTS};
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::file::InputOptions;
+use advent_of_code_2023::problems::{find_solution, VARIANTS};
+use advent_of_code_2023::solution::{downcast, Parsed, Part, Solution, Solved};
+
+// A solution that panics on any input containing `panics_on`, and otherwise answers
+// with the input's length.
+struct PanicsOn(&'static str);
+
+impl Solution for PanicsOn {
+    fn day(&self) -> u32 { 1 }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { "Panics" }
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        if input.contains(self.0) {
+            panic!("can't handle '{}'", self.0);
+        }
+        Ok(Box::new(input.len() as u64))
+    }
+    fn solve(&self, parsed: &Parsed) -> Result<Solved, Error> {
+        Ok(Solved::new(*downcast::<u64>(parsed)?))
+    }
+}
+
+static PANICS_ON_X: PanicsOn = PanicsOn("x");
+static ALSO_PANICS_ON_X: PanicsOn = PanicsOn("x");
+static PANICS_ON_Y: PanicsOn = PanicsOn("y");
+
+fn inputs(contents: &[&str]) -> Vec<CrosscheckInput> {
+    contents.iter().map(|contents| CrosscheckInput { name: contents.to_string(), contents: contents.to_string() }).collect()
+}
+
+#[test]
+fn variants_agree_with_main_implementations() {
+    let mut days = VARIANTS.iter().map(|solution| solution.day()).collect::<Vec<u32>>();
+    days.dedup();
+    for day in days {
+        for crosscheck in crosscheck_day(day, &InputOptions::default(), 0, 30) {
+            let disagreement = crosscheck.disagreement.map(|disagreement| (disagreement.input_name, disagreement.minimized_input));
+            assert!(disagreement.is_none(), "{} disagrees with a variant: {:?}", crosscheck.implementations[0].selector(), disagreement);
+        }
+    }
+}
+
+#[test]
+fn implementations_panicking_on_the_same_input_agree() {
+    let result = crosscheck(vec![&PANICS_ON_X, &ALSO_PANICS_ON_X], &inputs(&["abc\n", "axc\n"]));
+    assert_eq!(result.inputs_checked, 2);
+    assert!(result.disagreement.is_none());
+}
+
+#[test]
+fn panics_disagree_with_answers() {
+    let result = crosscheck(vec![&PANICS_ON_X, &PANICS_ON_Y], &inputs(&["abc\n", "ab\nx\nc\n", "x\n"]));
+    assert_eq!(result.inputs_checked, 2);
+    let disagreement = result.disagreement.unwrap();
+    assert_eq!(disagreement.input_name, "ab\nx\nc\n");
+    assert_eq!(disagreement.minimized_input, "x\n");
+    match disagreement.minimized_outcomes.as_slice() {
+        [Outcome::Panicked(message), Outcome::Answer(answer)] => assert_eq!((message.as_str(), answer.as_str()), ("can't handle 'x'", "2")),
+        other => panic!("expected a panic and an answer, got {:?}", other),
+    }
+}
+
+#[test]
+fn seeds_near_the_end_wrap_around() {
+    let options = InputOptions { dir: String::from("does-not-exist"), ..InputOptions::default() };
+    let inputs = crosscheck_inputs(find_solution("3a").unwrap(), &options, u64::MAX - 1, 5);
+    let names = inputs.iter().map(|input| input.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names.len() as u64, GENERATED_INPUTS);
+    assert_eq!(names[..3], [format!("generated (seed {})", u64::MAX - 1), format!("generated (seed {})", u64::MAX), String::from("generated (seed 0)")]);
+}
+
+#[test]
+fn only_days_with_variants_are_crosschecked() {
+    for day in 1..=5 {
+        assert_eq!(has_variants(day), VARIANTS.iter().any(|solution| solution.day() == day), "day {}", day);
+    }
+}
+
+#[test]
+fn minimizes_failing_input() {
+    // fails whenever a line contains both an 'x' and a 'y'
+    let still_fails = |input: &str| input.lines().any(|line| line.contains('x') && line.contains('y'));
+    let minimized = minimize_input("abc\ndef\n12x45y78\nghi\n", still_fails);
+    assert_eq!(minimized, "xy\n");
+
+    // columns are removed from every line at once
+    let still_fails = |input: &str| input.lines().nth(1).is_some_and(|line| line.starts_with('#')) && input.lines().count() == 2;
+    let minimized = minimize_input("..#..\n..#..\n", still_fails);
+    assert_eq!(minimized, "#\n#\n");
+}
@@ -0,0 +1,32 @@
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::problems::problem01::{find_first_digit, problem01_part_2};
+
+#[test]
+fn parse_error_points_at_the_middle_of_a_line() {
+    let line = "Game 1: 3 blue, 4 grean";
+    let err = Error::parse_at(4, line, &line[18..], "a colour").in_file("problems/02.txt");
+    assert_eq!(
+        err.to_string(),
+        "Parse error at problems/02.txt:5:19 - expected a colour\n\
+         5 | Game 1: 3 blue, 4 grean\n  \
+           |                   ^"
+    );
+}
+
+#[test]
+fn parse_error_points_past_the_end_of_a_line() {
+    let line = "héllo";
+    let err = Error::parse(0, line, line.len(), "a digit");
+    // the column counts characters, not bytes
+    assert_eq!(err.to_string(), "Parse error at line 1, column 6 - expected a digit\n1 | héllo\n  |      ^");
+}
+
+#[test]
+fn day_1_searches_non_ascii_lines() {
+    assert_eq!(find_first_digit("ébone7é", false), Some('1'));
+    assert_eq!(find_first_digit("ébone7é", true), Some('7'));
+    assert_eq!(find_first_digit("zér€", false), None);
+
+    let err = problem01_part_2(&[String::from("twö")]).unwrap_err();
+    assert!(matches!(err, Error::Parse { line: 1, column: 4, .. }), "{:?}", err);
+}
@@ -0,0 +1,38 @@
+// Regression tests running every solution against its example input, checked against
+// the answers from the puzzle text recorded in `problems/example-answers.txt`.
+
+use advent_of_code_2023::answers::{Answers, DEFAULT_EXAMPLE_ANSWERS_FILE};
+use advent_of_code_2023::file::InputOptions;
+use advent_of_code_2023::problems::find_solution;
+use advent_of_code_2023::runner::RunOptions;
+use advent_of_code_2023::verify::{verify_all, Verdict};
+
+fn example_answers() -> Answers {
+    Answers::load(DEFAULT_EXAMPLE_ANSWERS_FILE).expect("example answers should parse")
+}
+
+#[test]
+fn examples_produce_expected_answers() {
+    let input_options = InputOptions { example: true, ..InputOptions::default() };
+    let verifications = verify_all(&example_answers(), &input_options, &RunOptions::default());
+
+    let mut failures = Vec::new();
+    for verification in &verifications {
+        if let Verdict::Fail { expected, actual } = &verification.verdict {
+            let actual = match (actual, verification.result.as_ref().map(|result| &result.answer)) {
+                (Some(actual), _) => actual.clone(),
+                (None, Some(Err(err))) => format!("error: {}", err),
+                (None, _) => String::from("no answer"),
+            };
+            failures.push(format!("{}: expected {}, got {}", verification.solution.selector(), expected, actual));
+        }
+    }
+    assert!(failures.is_empty(), "example answers did not match:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn example_answers_are_for_registered_parts() {
+    let answers = example_answers();
+    let unknown = answers.selectors().filter(|selector| find_solution(selector).is_none()).collect::<Vec<&str>>();
+    assert!(unknown.is_empty(), "example answers recorded for unknown parts: {}", unknown.join(", "));
+}
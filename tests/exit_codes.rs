@@ -0,0 +1,61 @@
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{self, Command, Stdio};
+
+// Run the binary from the crate root (where the default inputs live) with `stdin` piped
+// in, returning its exit code.
+fn exit_code(args: &[&str], stdin: &str) -> i32 {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_advent-of-code-2023"))
+        .args(args)
+        .arg("--quiet")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    // the binary may exit without reading stdin, e.g. on a usage error
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    return child.wait().unwrap().code().unwrap();
+}
+
+#[test]
+fn success() {
+    assert_eq!(exit_code(&["2a", "--example"], ""), 0);
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(exit_code(&[], ""), 2);
+    assert_eq!(exit_code(&["--bogus"], ""), 2);
+    assert_eq!(exit_code(&["9z"], ""), 2);
+    assert_eq!(exit_code(&["2a", "--timeout", "-1"], ""), 2);
+    assert_eq!(exit_code(&["crosscheck", "1"], ""), 2);
+}
+
+#[test]
+fn io_errors() {
+    assert_eq!(exit_code(&["2a", "does-not-exist.txt"], ""), 3);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(exit_code(&["1a", "-"], "abc\n"), 4);
+    assert_eq!(exit_code(&["2a", "-"], "Game 1: 3 purple\n"), 4);
+}
+
+#[test]
+fn verification_mismatches() {
+    let path = env::temp_dir().join(format!("aoc-exit-codes-{}.txt", process::id()));
+    fs::write(&path, "2a 9\n").unwrap();
+    let mismatch = exit_code(&["verify", path.to_str().unwrap(), "--example"], "");
+    fs::write(&path, "2a 8\n").unwrap();
+    let matched = exit_code(&["verify", path.to_str().unwrap(), "--example"], "");
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(mismatch, 6);
+    assert_eq!(matched, 0);
+}
@@ -0,0 +1,174 @@
+#![allow(clippy::needless_return)]
+
+// Tests for the input fetcher against a stand-in server on localhost, which answers
+// each connection with a canned response and records the requests it received.
+
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use advent_of_code_2023::fetch::{fetch_input, FetchConfig, Fetched};
+
+// Serve one connection per response, in order, returning the raw requests received.
+fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+    let base_url = format!("http://{}/2023", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().expect("accept connection");
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).expect("read request");
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(response.as_bytes()).expect("write response");
+            requests.push(String::from_utf8(request).unwrap());
+        }
+        return requests;
+    });
+    return (base_url, handle);
+}
+
+fn ok_response(body: &str) -> String {
+    format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+fn config(base_url: &str) -> FetchConfig {
+    FetchConfig {
+        base_url: base_url.to_string(),
+        session: Some(String::from("test-session")),
+        min_interval: Duration::ZERO,
+        ..FetchConfig::default()
+    }
+}
+
+// A fresh, empty directory for a test's inputs.
+fn input_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc-fetch-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    return dir;
+}
+
+#[test]
+fn downloads_and_caches_input() {
+    let (base_url, server) = serve(vec![ok_response("1 2 3\n4 5 6\n")]);
+    let dir = input_dir("download");
+
+    let fetched = fetch_input(&config(&base_url), &dir, 3).unwrap();
+    assert_eq!(fetched, Fetched::Downloaded(dir.join("03.txt")));
+    assert_eq!(fs::read_to_string(dir.join("03.txt")).unwrap(), "1 2 3\n4 5 6\n");
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("GET /2023/day/3/input HTTP/1.1\r\n"), "{}", requests[0]);
+    assert!(requests[0].contains("\r\nCookie: session=test-session\r\n"), "{}", requests[0]);
+    assert!(requests[0].contains("\r\nUser-Agent: advent-of-code-2023/"), "{}", requests[0]);
+}
+
+#[test]
+fn never_refetches_cached_input() {
+    // the server accepts no connections, so any request would fail
+    let (base_url, server) = serve(vec![]);
+    server.join().unwrap();
+    let dir = input_dir("cached");
+    fs::write(dir.join("04.txt"), "cached input\n").unwrap();
+
+    let fetched = fetch_input(&config(&base_url), &dir, 4).unwrap();
+    assert_eq!(fetched, Fetched::Cached(dir.join("04.txt")));
+    assert_eq!(fs::read_to_string(dir.join("04.txt")).unwrap(), "cached input\n");
+}
+
+#[test]
+fn empty_placeholder_input_is_fetched() {
+    let (base_url, server) = serve(vec![ok_response("real input\n")]);
+    let dir = input_dir("placeholder");
+    fs::write(dir.join("06.txt"), "").unwrap();
+
+    let fetched = fetch_input(&config(&base_url), &dir, 6).unwrap();
+    assert_eq!(fetched, Fetched::Downloaded(dir.join("06.txt")));
+    assert_eq!(fs::read_to_string(dir.join("06.txt")).unwrap(), "real input\n");
+    server.join().unwrap();
+}
+
+#[test]
+fn decodes_chunked_responses() {
+    let response = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n6\r\nseeds:\r\n7\r\n 79 14\n\r\n0\r\n\r\n";
+    let (base_url, server) = serve(vec![response.to_string()]);
+    let dir = input_dir("chunked");
+
+    fetch_input(&config(&base_url), &dir, 5).unwrap();
+    assert_eq!(fs::read_to_string(dir.join("05.txt")).unwrap(), "seeds: 79 14\n");
+    server.join().unwrap();
+}
+
+#[test]
+fn failed_fetch_is_not_cached() {
+    let not_found = "HTTP/1.1 404 Not Found\r\nContent-Length: 9\r\n\r\nNot Found";
+    let bad_session = "HTTP/1.1 400 Bad Request\r\nContent-Length: 43\r\n\r\nPlease log in to get your puzzle input.\r\n\r\n";
+    let (base_url, server) = serve(vec![not_found.to_string(), bad_session.to_string()]);
+    let dir = input_dir("failed");
+
+    let err = fetch_input(&config(&base_url), &dir, 7).unwrap_err();
+    assert!(err.to_string().contains("404"), "{}", err);
+    let err = fetch_input(&config(&base_url), &dir, 7).unwrap_err();
+    assert!(err.to_string().contains("Please log in"), "{}", err);
+    assert!(!dir.join("07.txt").exists());
+    server.join().unwrap();
+}
+
+#[test]
+fn truncated_download_is_not_cached() {
+    let truncated = "HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\nseeds: 79 14\n";
+    let truncated_chunk = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n64\r\nseeds: 79 14\n";
+    let (base_url, server) = serve(vec![truncated.to_string(), truncated_chunk.to_string()]);
+    let dir = input_dir("truncated");
+
+    let err = fetch_input(&config(&base_url), &dir, 8).unwrap_err();
+    assert!(err.to_string().contains("malformed"), "{}", err);
+    let err = fetch_input(&config(&base_url), &dir, 8).unwrap_err();
+    assert!(err.to_string().contains("malformed"), "{}", err);
+    assert!(!dir.join("08.txt").exists());
+    server.join().unwrap();
+}
+
+#[test]
+fn missing_session_is_an_error() {
+    let dir = input_dir("no-session");
+    let config = FetchConfig { session: None, ..config("http://127.0.0.1:9") };
+
+    let err = fetch_input(&config, &dir, 1).unwrap_err();
+    assert!(err.to_string().contains("session"), "{}", err);
+}
+
+#[test]
+fn requests_are_rate_limited() {
+    let (base_url, server) = serve(vec![ok_response("first\n"), ok_response("second\n")]);
+    let dir = input_dir("rate-limit");
+    let config = FetchConfig { min_interval: Duration::from_millis(300), ..config(&base_url) };
+
+    let start = Instant::now();
+    fetch_input(&config, &dir, 1).unwrap();
+    fetch_input(&config, &dir, 2).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(300), "second request after {:?}", start.elapsed());
+    server.join().unwrap();
+}
+
+#[test]
+fn parses_config_file() {
+    let config = FetchConfig::parse("# fetch settings\nsession = abc123\nbase_url = http://localhost:8080/2023\n").unwrap();
+    assert_eq!(config.session.as_deref(), Some("abc123"));
+    assert_eq!(config.base_url, "http://localhost:8080/2023");
+
+    assert!(FetchConfig::parse("token = abc123\n").is_err());
+}
@@ -0,0 +1,58 @@
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::problems::{generate_input, SOLUTIONS, VARIANTS};
+use advent_of_code_2023::rng::Rng;
+
+// Small enough to keep the slower days quick, while still exercising every generator.
+const SIZE: usize = 8;
+const SEEDS: u64 = 10;
+
+// Days without an input generator (e.g. one just scaffolded with `new-day`) are skipped.
+#[test]
+fn the_same_seed_generates_the_same_input() {
+    for solution in SOLUTIONS {
+        for seed in 0..SEEDS {
+            let input = solution.generate(&mut Rng::new(seed), SIZE);
+            assert_eq!(input, solution.generate(&mut Rng::new(seed), SIZE), "{} with seed {}", solution.selector(), seed);
+        }
+    }
+}
+
+#[test]
+fn different_seeds_generate_different_inputs() {
+    for solution in SOLUTIONS {
+        let inputs = (0..SEEDS).filter_map(|seed| solution.generate(&mut Rng::new(seed), SIZE)).collect::<Vec<String>>();
+        let mut distinct = inputs.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), inputs.len(), "{} generated the same input from different seeds", solution.selector());
+    }
+}
+
+#[test]
+fn generated_inputs_solve() {
+    for solution in SOLUTIONS.iter().chain(VARIANTS) {
+        for seed in 0..SEEDS {
+            let input = match solution.generate(&mut Rng::new(seed), SIZE) {
+                Some(input) => input,
+                None => continue,
+            };
+            if let Err(err) = solution.solve_input(&input) {
+                panic!("{} failed on the input generated from seed {}: {}\n{}", solution.selector(), seed, err, input);
+            }
+        }
+    }
+}
+
+#[test]
+fn generates_inputs_by_day() {
+    for solution in SOLUTIONS {
+        let expected = solution.generate(&mut Rng::new(42), SIZE);
+        match generate_input(solution.day(), Some(42), SIZE) {
+            Ok(input) => assert_eq!(Some(input), expected, "day {}", solution.day()),
+            Err(err) => assert!(expected.is_none(), "day {}: {}", solution.day(), err),
+        }
+    }
+
+    let missing_day = SOLUTIONS.iter().map(|solution| solution.day()).max().unwrap() + 1;
+    assert!(matches!(generate_input(missing_day, Some(42), SIZE), Err(Error::Validation(_))));
+}
@@ -0,0 +1,74 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use advent_of_code_2023::json::Json;
+use advent_of_code_2023::problems::find_solution;
+use advent_of_code_2023::runner::{run_solution, RunOptions};
+
+fn keys(json: &Json) -> Vec<&str> {
+    match json {
+        Json::Object(fields) => fields.iter().map(|(key, _)| key.as_str()).collect(),
+        other => panic!("expected an object, got {}", other),
+    }
+}
+
+fn field<'a>(json: &'a Json, key: &str) -> &'a Json {
+    match json {
+        Json::Object(fields) => &fields.iter().find(|(field, _)| field == key).unwrap_or_else(|| panic!("no field '{}' in {}", key, json)).1,
+        other => panic!("expected an object, got {}", other),
+    }
+}
+
+#[test]
+fn escapes_strings() {
+    assert_eq!(Json::from("say \"hi\"").to_string(), r#""say \"hi\"""#);
+    assert_eq!(Json::from(r"C:\inputs").to_string(), r#""C:\\inputs""#);
+    assert_eq!(Json::from("a\nb\r\tc").to_string(), r#""a\nb\r\tc""#);
+    assert_eq!(Json::from("\u{0}\u{1b}\u{1f} ").to_string(), r#""\u0000\u001b\u001f ""#);
+    // everything else, including non-ASCII and DEL, is written as is
+    assert_eq!(Json::from("é ☃ 🎄 \u{7f}").to_string(), "\"é ☃ 🎄 \u{7f}\"");
+}
+
+#[test]
+fn writes_nested_values() {
+    let json = Json::object()
+        .with("list", vec![Json::from(1u32), Json::Null, Json::from(vec!["x"]), Json::object()])
+        .with("inner", Json::object().with("ok", true).with("none", None::<u64>))
+        .with("big", u128::MAX)
+        .with("ratio", f64::NAN)
+        .with("empty", Vec::<Json>::new());
+    assert_eq!(
+        json.to_string(),
+        r#"{"list":[1,null,["x"],{}],"inner":{"ok":true,"none":null},"big":340282366920938463463374607431768211455,"ratio":null,"empty":[]}"#
+    );
+}
+
+#[test]
+fn results_have_a_stable_set_of_fields() {
+    let result = run_solution(find_solution("3a").unwrap(), "problems/03example.txt", &RunOptions::default()).to_json();
+    assert_eq!(keys(&result), ["day", "part", "variant", "status", "answer", "label", "timings", "allocations", "input", "error"]);
+    assert_eq!(field(&result, "day"), &Json::from(3u32));
+    assert_eq!(field(&result, "part"), &Json::from("a"));
+    assert_eq!(field(&result, "status"), &Json::from("ok"));
+    assert_eq!(field(&result, "answer"), &Json::from(4361u64));
+    assert_eq!(field(&result, "error"), &Json::Null);
+    assert_eq!(keys(field(&result, "timings")), ["read_ns", "parse_ns", "solve_ns", "total_ns"]);
+}
+
+#[test]
+fn failed_results_describe_the_error() {
+    let path = env::temp_dir().join(format!("aoc-json-{}.txt", process::id()));
+    fs::write(&path, "abc\n").unwrap();
+    let result = run_solution(find_solution("1a").unwrap(), path.to_str().unwrap(), &RunOptions::default()).to_json();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(field(&result, "status"), &Json::from("error"));
+    assert_eq!(field(&result, "answer"), &Json::Null);
+    let error = field(&result, "error");
+    assert_eq!(keys(error), ["kind", "message", "file", "line", "column", "snippet", "expected"]);
+    assert_eq!(field(error, "kind"), &Json::from("parse"));
+    assert_eq!(field(error, "line"), &Json::from(1usize));
+    assert_eq!(field(error, "column"), &Json::from(4usize));
+    assert_eq!(field(error, "snippet"), &Json::from("abc"));
+}
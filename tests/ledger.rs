@@ -0,0 +1,127 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use advent_of_code_2023::answers::Answers;
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::ledger::{Feedback, Guess, GuessOutcome, Ledger, Warning};
+
+fn guess(selector: &str, answer: &str, feedback: Feedback) -> Guess {
+    Guess { selector: selector.to_string(), answer: answer.to_string(), feedback }
+}
+
+#[test]
+fn parses_guesses_skipping_blank_lines_and_comments() {
+    let ledger = Ledger::parse("# day 5\n5a too-high 200\n\n5a Too_Low 100\n5b wrong not a number\n").unwrap();
+    assert_eq!(
+        ledger.guesses("5a").cloned().collect::<Vec<Guess>>(),
+        [guess("5a", "200", Feedback::TooHigh), guess("5a", "100", Feedback::TooLow)]
+    );
+    assert_eq!(ledger.guesses("5b").cloned().collect::<Vec<Guess>>(), [guess("5b", "not a number", Feedback::Wrong)]);
+    assert_eq!(ledger.guesses("4a").count(), 0);
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    match Ledger::parse("5a too-high 200\n5a too-high\n") {
+        Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 12)),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+    match Ledger::parse("5a too-hi 200\n") {
+        Err(Error::Parse { line, column, expected, .. }) => {
+            assert_eq!((line, column), (1, 4));
+            assert!(expected.contains("too-high"), "{}", expected);
+        }
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn bounds_are_the_tightest_too_high_and_too_low_guesses() {
+    let ledger = Ledger::parse("5a too-high 200\n5a too-low 100\n5a too-high 150\n5a too-low 90\n5a wrong 120\n6a too-low 500\n").unwrap();
+    assert_eq!(ledger.bounds("5a"), (Some(100), Some(150)));
+    assert_eq!(ledger.bounds("6a"), (Some(500), None));
+    assert_eq!(ledger.bounds("7a"), (None, None));
+
+    let ledger = Ledger::parse("5a too-high 200\n5a too-high abc\n").unwrap();
+    assert_eq!(ledger.bounds("5a"), (None, Some(200)));
+}
+
+#[test]
+fn checks_answers_against_earlier_guesses() {
+    let ledger = Ledger::parse("5a too-high 200\n5a too-low 100\n5a wrong 120\n").unwrap();
+    assert_eq!(ledger.check("5a", "120"), Some(Warning::KnownWrong(Feedback::Wrong)));
+    assert_eq!(ledger.check("5a", "200"), Some(Warning::KnownWrong(Feedback::TooHigh)));
+    assert_eq!(ledger.check("5a", "250"), Some(Warning::AboveBound(String::from("200"))));
+    assert_eq!(ledger.check("5a", "100"), Some(Warning::KnownWrong(Feedback::TooLow)));
+    assert_eq!(ledger.check("5a", "-3"), Some(Warning::BelowBound(String::from("100"))));
+    assert_eq!(ledger.check("5a", "150"), None);
+    assert_eq!(ledger.check("5a", "abc"), None);
+    assert_eq!(ledger.check("5b", "250"), None);
+
+    let ledger = Ledger::parse("5a wrong 120\n5a correct 130\n").unwrap();
+    assert_eq!(ledger.check("5a", "130"), None);
+    assert_eq!(ledger.check("5a", "140"), Some(Warning::NotCorrect(String::from("130"))));
+}
+
+#[test]
+fn repeated_guesses_replace_the_earlier_feedback() {
+    let mut ledger = Ledger::parse("5a too-high 200\n5a wrong 120\n").unwrap();
+    ledger.record(guess("5a", "200", Feedback::TooLow));
+    assert_eq!(
+        ledger.guesses("5a").cloned().collect::<Vec<Guess>>(),
+        [guess("5a", "200", Feedback::TooLow), guess("5a", "120", Feedback::Wrong)]
+    );
+    assert_eq!(ledger.bounds("5a"), (Some(200), None));
+    assert_eq!(ledger.check("5a", "200"), Some(Warning::KnownWrong(Feedback::TooLow)));
+}
+
+#[test]
+fn recording_guesses_saves_them_and_correct_answers() {
+    let dir = env::temp_dir().join(format!("aoc-ledger-{}", process::id()));
+    let ledger_path = dir.join("guesses.txt");
+    let ledger_path = ledger_path.to_str().unwrap();
+    let answers_path = dir.join("answers.txt");
+    let answers_path = answers_path.to_str().unwrap();
+
+    let mut ledger = Ledger::load(ledger_path).unwrap();
+    let outcome = ledger.record_guess(guess("5a", "200", Feedback::TooHigh), ledger_path, answers_path).unwrap();
+    assert_eq!(outcome, GuessOutcome::Wrong { lower: None, upper: Some(200) });
+    let outcome = ledger.record_guess(guess("5a", "100", Feedback::TooLow), ledger_path, answers_path).unwrap();
+    assert_eq!(outcome, GuessOutcome::Wrong { lower: Some(100), upper: Some(200) });
+    assert!(!dir.join("answers.txt").exists());
+
+    let outcome = ledger.record_guess(guess("5a", "150", Feedback::Correct), ledger_path, answers_path).unwrap();
+    assert_eq!(outcome, GuessOutcome::Correct);
+    let saved = fs::read_to_string(ledger_path).unwrap();
+    let answers = Answers::load(answers_path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(saved, "5a too-high 200\n5a too-low 100\n5a correct 150\n");
+    assert_eq!(answers.get("5a"), Some("150"));
+}
+
+#[test]
+fn saving_keeps_comments_and_blank_lines() {
+    let dir = env::temp_dir().join(format!("aoc-ledger-comments-{}", process::id()));
+    let ledger_path = dir.join("guesses.txt");
+    let ledger_path = ledger_path.to_str().unwrap();
+    let answers_path = dir.join("answers.txt");
+    let answers_path = answers_path.to_str().unwrap();
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(ledger_path, "# guesses so far\n5a too-high 200\n\n# part 2\n5b wrong 7\n").unwrap();
+    fs::write(answers_path, "# checked by hand\n1a 142\n\n# day 5\n5a 99\n").unwrap();
+
+    let mut ledger = Ledger::load(ledger_path).unwrap();
+    ledger.record_guess(guess("5a", "200", Feedback::Wrong), ledger_path, answers_path).unwrap();
+    ledger.record_guess(guess("5a", "150", Feedback::Correct), ledger_path, answers_path).unwrap();
+    ledger.record_guess(guess("5b", "8", Feedback::Correct), ledger_path, answers_path).unwrap();
+    let saved_ledger = fs::read_to_string(ledger_path).unwrap();
+    let saved_answers = fs::read_to_string(answers_path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(saved_ledger, "# guesses so far\n5a wrong 200\n\n# part 2\n5b wrong 7\n5a correct 150\n5b correct 8\n");
+    assert_eq!(saved_answers, "# checked by hand\n1a 142\n\n# day 5\n5a 150\n5b 8\n");
+    let answers = Answers::parse(&saved_answers).unwrap();
+    assert_eq!((answers.get("1a"), answers.get("5a"), answers.get("5b")), (Some("142"), Some("150"), Some("8")));
+}
@@ -0,0 +1,32 @@
+use advent_of_code_2023::memory::{format_bytes, is_enabled, measure, AllocationStats};
+
+#[test]
+fn measures_allocations_when_enabled() {
+    let (vector, stats) = measure(|| {
+        let scratch = vec![0u8; 4096];
+        drop(scratch);
+        Vec::<u64>::with_capacity(16)
+    });
+    assert_eq!(vector.capacity(), 16);
+    if !is_enabled() {
+        assert_eq!(stats, None);
+        return;
+    }
+
+    let stats = stats.unwrap();
+    assert_eq!(stats.allocations, 2);
+    assert_eq!(stats.bytes_allocated, 4096 + 16 * 8);
+    assert_eq!(stats.peak_live_bytes, 4096);
+    assert_eq!(stats.retained_bytes, 16 * 8);
+}
+
+#[test]
+fn chained_stats_include_memory_retained_before() {
+    let parse = AllocationStats { allocations: 3, bytes_allocated: 1000, peak_live_bytes: 800, retained_bytes: 600 };
+    let solve = AllocationStats { allocations: 2, bytes_allocated: 500, peak_live_bytes: 300, retained_bytes: -100 };
+    let run = parse.then(solve);
+    assert_eq!(run, AllocationStats { allocations: 5, bytes_allocated: 1500, peak_live_bytes: 900, retained_bytes: 500 });
+
+    assert_eq!(format_bytes(512), "512 B");
+    assert_eq!(format_bytes(3 * 1024 * 1024 + 200 * 1024), "3.2 MiB");
+}
@@ -0,0 +1,45 @@
+use std::panic;
+use std::thread;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use advent_of_code_2023::crosscheck::{crosscheck, CrosscheckInput};
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::solution::{Parsed, Part, Solution, Solved};
+
+// Kept apart from the other cross-check tests, since the panic hook is global to the
+// test binary.
+
+struct AlwaysPanics;
+
+impl Solution for AlwaysPanics {
+    fn day(&self) -> u32 { 1 }
+    fn part(&self) -> Part { Part::A }
+    fn title(&self) -> &'static str { "Always panics" }
+    fn parse(&self, _input: &str) -> Result<Parsed, Error> {
+        panic!("always");
+    }
+    fn solve(&self, _parsed: &Parsed) -> Result<Solved, Error> {
+        Ok(Solved::new(0u32))
+    }
+}
+
+static ALWAYS_PANICS: AlwaysPanics = AlwaysPanics;
+
+static PANICS_SEEN: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn crosscheck_only_silences_its_own_panics() {
+    panic::set_hook(Box::new(|_| {
+        PANICS_SEEN.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    let inputs = [CrosscheckInput { name: String::from("input"), contents: String::from("abc\n") }];
+    let result = crosscheck(vec![&ALWAYS_PANICS, &ALWAYS_PANICS], &inputs);
+    assert!(result.disagreement.is_none());
+    assert_eq!(PANICS_SEEN.load(Ordering::Relaxed), 0);
+
+    // panics outside of a cross-check, on this thread or another, still reach the hook
+    assert!(panic::catch_unwind(|| panic!("after")).is_err());
+    assert!(thread::spawn(|| panic!("elsewhere")).join().is_err());
+    assert_eq!(PANICS_SEEN.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,153 @@
+#![allow(clippy::needless_return)]
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use advent_of_code_2023::cancel::{with_token, CancellationToken};
+use advent_of_code_2023::progress::{clear_cancelled, format_progress, report, with_progress, with_progress_on, Output, RENDER_INTERVAL};
+
+// Tests that draw a progress line take turns, since only one reporter can hold the line.
+static DRAWING: Mutex<()> = Mutex::new(());
+
+// An output that records what would have been written to the terminal.
+fn capture() -> (Output, Arc<Mutex<String>>) {
+    let text = Arc::new(Mutex::new(String::new()));
+    let written = Arc::clone(&text);
+    let output: Output = Arc::new(move |line: &str| written.lock().unwrap().push_str(line));
+    return (output, text);
+}
+
+// What was written, split into the lines drawn (the escape codes that redraw the line
+// start each one), so that an empty line at the end is the line being cleared.
+fn drawn(text: &Arc<Mutex<String>>) -> Vec<String> {
+    let text = text.lock().unwrap();
+    return text.split("\r\x1b[K").skip(1).map(String::from).collect();
+}
+
+#[test]
+fn formats_progress_with_rate_and_eta() {
+    let line = format_progress("5b", 904_000_000, 2_000_000_000, Duration::from_secs(80));
+    assert_eq!(line, "5b   45.2%  904.0M/2.0G  11.3M/s  ETA 1m36s");
+
+    let line = format_progress("4b", 0, 202, Duration::ZERO);
+    assert_eq!(line, "4b    0.0%  0/202  0/s  ETA ?");
+}
+
+#[test]
+fn reporting_without_a_reporter_does_nothing() {
+    // nothing is drawn outside of `with_progress`, and parts that finish quickly draw nothing
+    report(1, 2);
+    assert_eq!(with_progress("1a", || 42), 42);
+}
+
+#[test]
+fn quick_parts_never_draw() {
+    let _drawing = DRAWING.lock().unwrap_or_else(PoisonError::into_inner);
+    let (output, text) = capture();
+    let value = with_progress_on("1a", output, || {
+        report(1, 2);
+        report(2, 2);
+        return 42;
+    });
+    assert_eq!(value, 42);
+    assert_eq!(text.lock().unwrap().as_str(), "");
+}
+
+#[test]
+fn draws_at_most_once_per_interval_and_clears_the_line() {
+    let _drawing = DRAWING.lock().unwrap_or_else(PoisonError::into_inner);
+    let (output, text) = capture();
+    with_progress_on("5b", output, || {
+        thread::sleep(RENDER_INTERVAL);
+        report(2, 10);
+        report(3, 10);
+        assert_eq!(drawn(&text).len(), 1);
+        thread::sleep(RENDER_INTERVAL);
+        report(4, 10);
+    });
+
+    let lines = drawn(&text);
+    assert_eq!(lines.len(), 3, "{:?}", lines);
+    assert!(lines[0].starts_with("5b   20.0%  2/10  "), "{:?}", lines);
+    assert!(lines[1].starts_with("5b   40.0%  4/10  "), "{:?}", lines);
+    assert_eq!(lines[2], "");
+}
+
+#[test]
+fn only_one_part_draws_at_a_time() {
+    let _drawing = DRAWING.lock().unwrap_or_else(PoisonError::into_inner);
+    let (first_output, first_text) = capture();
+    let (second_output, second_text) = capture();
+    let (drawn_sender, drawn_receiver) = mpsc::channel();
+    let (done_sender, done_receiver) = mpsc::channel::<()>();
+
+    let first = thread::spawn(move || {
+        with_progress_on("4b", first_output, || {
+            thread::sleep(RENDER_INTERVAL);
+            report(1, 2);
+            drawn_sender.send(()).unwrap();
+            done_receiver.recv().unwrap();
+        })
+    });
+    with_progress_on("5b", second_output, || {
+        drawn_receiver.recv().unwrap();
+        thread::sleep(RENDER_INTERVAL);
+        // the first part still holds the line
+        report(1, 4);
+        assert!(drawn(&second_text).is_empty());
+
+        done_sender.send(()).unwrap();
+        first.join().unwrap();
+        // and has given it up once it finished
+        report(2, 4);
+    });
+
+    let first_lines = drawn(&first_text);
+    assert_eq!(first_lines.len(), 2, "{:?}", first_lines);
+    assert!(first_lines[0].starts_with("4b   50.0%  1/2  "), "{:?}", first_lines);
+    assert_eq!(first_lines[1], "");
+    let second_lines = drawn(&second_text);
+    assert_eq!(second_lines.len(), 2, "{:?}", second_lines);
+    assert!(second_lines[0].starts_with("5b   50.0%  2/4  "), "{:?}", second_lines);
+    assert_eq!(second_lines[1], "");
+}
+
+#[test]
+fn cancelled_parts_stop_drawing_once_cleared() {
+    let _drawing = DRAWING.lock().unwrap_or_else(PoisonError::into_inner);
+    let (output, text) = capture();
+    let token = CancellationToken::new();
+    with_token(token.clone(), || {
+        with_progress_on("4b", output, || {
+            thread::sleep(RENDER_INTERVAL);
+            report(1, 4);
+            // as the runner does when a part times out
+            token.cancel();
+            clear_cancelled();
+            thread::sleep(RENDER_INTERVAL);
+            report(2, 4);
+        })
+    });
+
+    let lines = drawn(&text);
+    assert_eq!(lines.len(), 2, "{:?}", lines);
+    assert!(lines[0].starts_with("4b   25.0%  1/4  "), "{:?}", lines);
+    assert_eq!(lines[1], "");
+}
+
+#[test]
+fn only_cancelled_parts_are_cleared() {
+    let _drawing = DRAWING.lock().unwrap_or_else(PoisonError::into_inner);
+    let (output, text) = capture();
+    with_token(CancellationToken::new(), || {
+        with_progress_on("5b", output, || {
+            thread::sleep(RENDER_INTERVAL);
+            report(1, 4);
+            clear_cancelled();
+            assert_eq!(drawn(&text).len(), 1);
+        })
+    });
+    assert_eq!(drawn(&text).len(), 2);
+}
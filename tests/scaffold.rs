@@ -0,0 +1,72 @@
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use advent_of_code_2023::scaffold::{new_day, register_day};
+
+const MOD_CONTENTS: &str = "\
+pub mod problem01;
+pub mod problem03;
+
+pub static SOLUTIONS: &[&dyn Solution] = &[
+    &problem01::Part1,
+    &problem01::Part2,
+    &problem03::Part1,
+    &problem03::Part2,
+];
+
+pub static VARIANTS: &[&dyn Solution] = &[
+    &problem01::Part1Fast,
+    &problem03::Part1Indexed,
+    &problem03::Part2Indexed,
+];
+";
+
+#[test]
+fn registers_day_in_order() {
+    let registered = register_day(MOD_CONTENTS, 2).unwrap();
+    let expected = MOD_CONTENTS
+        .replace("pub mod problem01;\n", "pub mod problem01;\npub mod problem02;\n")
+        .replace("    &problem01::Part2,\n", "    &problem01::Part2,\n    &problem02::Part1,\n    &problem02::Part2,\n");
+    assert_eq!(registered, expected);
+}
+
+#[test]
+fn registers_later_day_in_solutions_not_variants() {
+    let registered = register_day(MOD_CONTENTS, 6).unwrap();
+    let expected = MOD_CONTENTS
+        .replace("pub mod problem03;\n", "pub mod problem03;\npub mod problem06;\n")
+        .replace("    &problem03::Part2,\n];\n\npub static VARIANTS", "    &problem03::Part2,\n    &problem06::Part1,\n    &problem06::Part2,\n];\n\npub static VARIANTS");
+    assert_eq!(registered, expected);
+}
+
+#[test]
+fn rejects_registered_day() {
+    let err = register_day(MOD_CONTENTS, 3).unwrap_err();
+    assert!(err.to_string().contains("already registered"), "{}", err);
+}
+
+// A copy of the parts of the repository that `new-day` touches.
+fn scratch_root(name: &str) -> PathBuf {
+    let root = env::temp_dir().join(format!("aoc-scaffold-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src/problems")).unwrap();
+    fs::write(root.join("src/problems/mod.rs"), MOD_CONTENTS).unwrap();
+    return root;
+}
+
+#[test]
+fn example_test_reads_from_input_dir() {
+    let root = scratch_root("input-dir");
+    let input_dir = root.join("puzzles");
+
+    let changed = new_day(&root, &input_dir, 6).unwrap();
+    assert!(changed.contains(&input_dir.join("06example.txt")), "{:?}", changed);
+    let module = fs::read_to_string(root.join("src/problems/problem06.rs")).unwrap();
+    let include = format!("include_str!(\"{}\")", input_dir.join("06example.txt").display());
+    assert!(module.contains(&include), "no {} in:\n{}", include, module);
+    assert!(fs::read_to_string(root.join("src/problems/mod.rs")).unwrap().contains("    &problem06::Part2,\n];\n\npub static VARIANTS"));
+}
@@ -0,0 +1,83 @@
+use advent_of_code_2023::problems::{select_solutions, selector_help, SOLUTIONS, VARIANTS};
+
+fn selected(selector: &str) -> Vec<String> {
+    match select_solutions(selector) {
+        Ok(solutions) => solutions.iter().map(|solution| solution.selector()).collect(),
+        Err(err) => panic!("'{}' should be valid, got: {}", selector, err),
+    }
+}
+
+fn latest_day() -> u32 {
+    SOLUTIONS.iter().map(|solution| solution.day()).max().unwrap()
+}
+
+#[test]
+fn selects_single_parts_and_days() {
+    assert_eq!(selected("3b"), ["3b"]);
+    assert_eq!(selected("3"), ["3a", "3b"]);
+    assert_eq!(selected("1-2"), ["1a", "1b", "2a", "2b"]);
+    assert_eq!(selected("2-2"), ["2a", "2b"]);
+}
+
+#[test]
+fn selects_a_part_of_every_day() {
+    let every_a = SOLUTIONS.iter().map(|solution| solution.selector()).filter(|selector| selector.ends_with('a')).collect::<Vec<String>>();
+    assert_eq!(selected("a"), every_a);
+    assert!(selected("b").iter().all(|selector| selector.ends_with('b')));
+    assert_eq!(selected("a,b").len(), SOLUTIONS.len());
+}
+
+#[test]
+fn selects_latest_day() {
+    let latest = latest_day();
+    assert_eq!(selected("latest"), [format!("{}a", latest), format!("{}b", latest)]);
+}
+
+#[test]
+fn lists_are_in_day_order_without_duplicates() {
+    assert_eq!(selected("4a,2b"), ["2b", "4a"]);
+    assert_eq!(selected("3,3a, 3b"), ["3a", "3b"]);
+    assert_eq!(selected(" 1a , 1-1"), ["1a", "1b"]);
+}
+
+#[test]
+fn variants_are_only_selected_by_name() {
+    let variant = VARIANTS[0];
+    let day = variant.day().to_string();
+    assert!(!selected(&day).contains(&variant.selector()));
+    assert!(!selected("a,b").contains(&variant.selector()));
+
+    // a variant runs next to the main implementation of its part
+    let main = format!("{}{}", variant.day(), variant.part());
+    assert_eq!(selected(&format!("{},{}", variant.selector(), main)), [main.clone(), variant.selector()]);
+}
+
+#[test]
+fn rejects_invalid_selectors() {
+    for selector in ["", "3c", "x", "5-2", "1-", "-3", "1a:unknown", "3,,4"] {
+        let err = select_solutions(selector).err().unwrap_or_else(|| panic!("'{}' should be invalid", selector));
+        assert!(err.starts_with("Invalid problem selector"), "{}: {}", selector, err);
+    }
+}
+
+#[test]
+fn rejects_selectors_matching_nothing() {
+    let missing = latest_day() + 1;
+    let range = format!("{}-{}", missing, missing + 2);
+    for (selector, item) in [(missing.to_string(), missing.to_string()), (range.clone(), range), (format!("1a,{}", missing), missing.to_string())] {
+        let err = select_solutions(&selector).err().unwrap_or_else(|| panic!("'{}' should match nothing", selector));
+        assert!(err.starts_with(&format!("No problems match '{}'", item)), "{}: {}", selector, err);
+    }
+}
+
+#[test]
+fn errors_include_help_built_from_registry() {
+    let err = select_solutions("3c").err().unwrap();
+    assert!(err.ends_with(&selector_help()), "{}", err);
+
+    let help = selector_help();
+    for solution in SOLUTIONS.iter().chain(VARIANTS) {
+        assert!(help.contains(&solution.selector()), "{} missing from:\n{}", solution.selector(), help);
+    }
+    assert!(help.contains(&format!("(day {})", latest_day())), "{}", help);
+}
@@ -0,0 +1,40 @@
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Run the binary with `input` piped into stdin, returning the answer of each result it
+// prints, in order.
+fn answers_from_stdin(args: &[&str], input: &str) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_advent-of-code-2023"))
+        .args(args)
+        .args(["--quiet", "--format", "json"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{:?}", output.status);
+    return String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let answer = line.split("\"answer\":").nth(1).unwrap_or_else(|| panic!("no answer in {}", line));
+            answer.split(',').next().unwrap().to_string()
+        })
+        .collect();
+}
+
+#[test]
+fn every_part_reading_stdin_gets_the_whole_input() {
+    let example = fs::read_to_string("problems/03example.txt").unwrap();
+    // a variant parses separately from the main implementation, but stdin is only read once
+    assert_eq!(answers_from_stdin(&["3a,3a:indexed", "-"], &example), ["4361", "4361"]);
+    assert_eq!(answers_from_stdin(&["3a:indexed,3b", "-"], &example), ["4361", "467835"]);
+    assert_eq!(answers_from_stdin(&["3"], &example), ["4361", "467835"]);
+}
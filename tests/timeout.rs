@@ -0,0 +1,34 @@
+use std::time::{Duration, Instant};
+
+use advent_of_code_2023::cancel::{check, with_token, CancellationToken};
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::problems::find_solution;
+use advent_of_code_2023::runner::{run_solution, RunOptions};
+
+#[test]
+fn cancelled_token_fails_checks() {
+    assert!(check().is_ok());
+    let token = CancellationToken::new();
+    with_token(token.clone(), || {
+        assert!(check().is_ok());
+        token.cancel();
+        assert!(matches!(check(), Err(Error::Cancelled)));
+    });
+    // the token only applies within `with_token`
+    assert!(check().is_ok());
+}
+
+#[test]
+fn slow_parts_time_out() {
+    let options = RunOptions { timeout: Some(Duration::from_millis(200)), ..RunOptions::default() };
+
+    let start = Instant::now();
+    // day 4 part 2 takes far longer than this on the real input
+    let result = run_solution(find_solution("4b").unwrap(), "problems/04.txt", &options);
+    assert!(start.elapsed() < Duration::from_secs(5), "took {:?}", start.elapsed());
+    assert!(matches!(result.answer, Err(Error::Timeout(_))), "{:?}", result.answer);
+    assert_eq!(result.status(), "timeout");
+
+    let result = run_solution(find_solution("4b").unwrap(), "problems/04example.txt", &options);
+    assert_eq!(result.answer.unwrap().answer.to_string(), "30");
+}